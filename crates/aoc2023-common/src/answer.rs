@@ -0,0 +1,321 @@
+//! The [`Answer`] to one part of a puzzle.
+//!
+//! Most answers are numbers, but a few are words or pictures drawn in ASCII
+//! art. Answers are serialized as the same string they're displayed as, so the
+//! files they're saved in stay readable, and deserialized by guessing the
+//! variant from that string. The guess can't always be right, so answers are
+//! compared by value: `Unsigned(142)`, `Signed(142)` and `Text("142")` are all
+//! the same answer. Art is compared as the letters it spells if it can be
+//! read, or else as its characters, so it equals text with the same value.
+//! Comparing an answer with a `str` works the same way.
+
+use std::{
+    borrow::Cow,
+    convert::Infallible,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ocr::{self, OcrError};
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum Answer {
+    Signed(i128),
+    Unsigned(u128),
+    /// A single line of text.
+    Text(String),
+    /// Several lines of ASCII art, usually spelling out letters.
+    Art(String),
+}
+
+/// What an [`Answer`] is compared and hashed by, whichever variant it is.
+#[derive(PartialEq, Eq, Hash)]
+enum Value<'a> {
+    /// Any number that fits in an `i128`.
+    Number(i128),
+    /// A number too big for an `i128`.
+    Big(u128),
+    Str(Cow<'a, str>),
+}
+
+impl<'a> Value<'a> {
+    /// The value of some text, ignoring surrounding whitespace. Only numbers
+    /// written the way they're displayed count as numbers, so `+142` and `0142`
+    /// stay text.
+    fn of(s: Cow<'a, str>) -> Self {
+        let s = match s {
+            Cow::Borrowed(s) => Cow::Borrowed(s.trim()),
+            Cow::Owned(s) => Cow::Owned(s.trim().to_string()),
+        };
+        match (s.parse::<i128>(), s.parse::<u128>()) {
+            (Ok(n), _) if n.to_string() == s => Self::Number(n),
+            (_, Ok(n)) if n.to_string() == s => Self::Big(n),
+            _ => Self::Str(s),
+        }
+    }
+}
+
+impl Answer {
+    fn value(&self) -> Value<'_> {
+        match self {
+            Self::Signed(n) => Value::Number(*n),
+            Self::Unsigned(n) => i128::try_from(*n).map_or(Value::Big(*n), Value::Number),
+            Self::Text(s) => Value::of(Cow::Borrowed(s)),
+            Self::Art(art) => Value::of(ocr::read(art).map_or(Cow::Borrowed(art), Cow::Owned)),
+        }
+    }
+
+    /// An answer drawn as ASCII art, with trailing blank lines removed.
+    pub fn art(art: impl Into<String>) -> Self {
+        Self::Art(art.into().trim_end_matches('\n').to_string())
+    }
+
+    /// Whether this answer matches one typed in or submitted as `text`,
+    /// ignoring surrounding whitespace. Art matches either itself or the
+    /// letters it spells.
+    pub fn matches(&self, text: &str) -> bool {
+        self.value() == Self::from(text).value()
+    }
+
+    /// The answer as it should be submitted, with art read as letters.
+    pub fn submission(&self) -> Result<String, OcrError> {
+        match self {
+            Self::Art(art) => ocr::read(art),
+            _ => Ok(self.to_string()),
+        }
+    }
+
+    /// The answer on a single line, with art shown as the letters it spells,
+    /// or else summarised by its size.
+    pub fn compact(&self) -> String {
+        match self {
+            Self::Art(art) => {
+                if let Ok(text) = ocr::read(art) {
+                    return text;
+                }
+                let width = art.lines().map(|line| line.chars().count()).max();
+                let height = art.lines().count();
+                format!("{}x{height} art", width.unwrap_or(0))
+            },
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Signed(n) => write!(f, "{n}"),
+            Self::Unsigned(n) => write!(f, "{n}"),
+            Self::Text(s) | Self::Art(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value().hash(state);
+    }
+}
+
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        self.matches(other)
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches(other)
+    }
+}
+
+/// Implement `From` for integer types, via the widest type of the same sign.
+macro_rules! impl_from_int {
+    ($variant:ident($wide:ty): $($ty:ty),+) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(n: $ty) -> Self {
+                    Self::$variant(n as $wide)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_int!(Signed(i128): i8, i16, i32, i64, i128, isize);
+impl_from_int!(Unsigned(u128): u8, u16, u32, u64, u128, usize);
+
+impl From<String> for Answer {
+    /// Guess the variant from the text: numbers become [`Signed`](Self::Signed)
+    /// if they fit, multi-line text becomes [`Art`](Self::Art).
+    fn from(s: String) -> Self {
+        match Value::of(Cow::Borrowed(&s)) {
+            Value::Number(n) => Self::Signed(n),
+            Value::Big(n) => Self::Unsigned(n),
+            Value::Str(_) if s.contains('\n') => Self::art(s),
+            Value::Str(_) => Self::Text(s),
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        s.to_string().into()
+    }
+}
+
+impl From<Answer> for String {
+    fn from(answer: Answer) -> Self {
+        answer.to_string()
+    }
+}
+
+impl std::str::FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        Ok(s.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const ART: &str = "\
+#..#
+####
+#..#
+";
+
+    const LETTERS: &str = "\
+#..#.###
+#..#..#.
+####..#.
+#..#..#.
+#..#..#.
+#..#.###
+";
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Answer::from(-5i32), Answer::Signed(-5));
+        assert_eq!(Answer::from(7usize), Answer::Unsigned(7));
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".into()));
+        assert_eq!(Answer::from("123"), Answer::Signed(123));
+        assert_eq!(Answer::from(ART), Answer::art(ART));
+        assert_eq!(Answer::art(ART).to_string(), ART.trim_end());
+    }
+
+    #[test]
+    fn test_matches() {
+        assert_eq!(Answer::from(142u64), "142");
+        assert_eq!(Answer::from(-3i64), " -3\n");
+        assert_ne!(Answer::from(142u64), "143");
+        assert_ne!(Answer::from(142u64), "x");
+        assert_eq!(Answer::from("pzl"), "pzl");
+        assert!(Answer::art(ART).matches(ART));
+    }
+
+    #[test]
+    fn test_compact() {
+        assert_eq!(Answer::from(1_000_000u64).compact(), "1000000");
+        assert_eq!(Answer::art(ART).compact(), "4x3 art");
+        assert_eq!(Answer::art(LETTERS).compact(), "HI");
+    }
+
+    #[test]
+    fn test_submission() {
+        assert_eq!(Answer::from(-7i64).submission(), Ok("-7".to_string()));
+        assert_eq!(Answer::art(LETTERS).submission(), Ok("HI".to_string()));
+        assert!(Answer::art(LETTERS).matches("HI"));
+        assert!(Answer::art(ART).submission().is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let answers = vec![
+            Answer::Signed(-12),
+            Answer::Unsigned(u128::MAX),
+            Answer::Text("EHZRBHLP".into()),
+            Answer::art(ART),
+        ];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(
+            json,
+            r##"["-12","340282366920938463463374607431768211455","EHZRBHLP","#..#\n####\n#..#"]"##
+        );
+        assert_eq!(serde_json::from_str::<Vec<Answer>>(&json).unwrap(), answers);
+    }
+
+    #[test]
+    fn test_round_trips_are_equal() {
+        let answers = [
+            Answer::Unsigned(142),
+            Answer::Signed(-142),
+            Answer::Unsigned(u128::MAX),
+            Answer::Text("123".into()),
+            Answer::Text("pzl".into()),
+            Answer::Art("#..#".into()),
+            Answer::art(ART),
+        ];
+        for answer in answers {
+            let json = serde_json::to_string(&answer).unwrap();
+            let again: Answer = serde_json::from_str(&json).unwrap();
+            assert_eq!(again, answer);
+            assert_eq!(hash(&again), hash(&answer));
+
+            let toml = toml::to_string(&BTreeMap::from([("answer", &answer)])).unwrap();
+            let again: BTreeMap<String, Answer> = toml::from_str(&toml).unwrap();
+            assert_eq!(again["answer"], answer);
+
+            assert_eq!(answer.to_string().parse::<Answer>(), Ok(answer));
+        }
+    }
+
+    #[test]
+    fn test_equality() {
+        assert_eq!(Answer::art(LETTERS), Answer::Text("HI".into()));
+        assert_eq!(Answer::art(LETTERS), "HI");
+        assert_eq!(Answer::Art("5".into()), Answer::Text("5".into()));
+        assert_eq!(Answer::Art("5".into()), Answer::Signed(5));
+        assert_ne!(Answer::Text("+142".into()), Answer::Unsigned(142));
+        assert_ne!(Answer::Unsigned(142), "+142");
+        assert_ne!(Answer::Text("0142".into()), Answer::Text("142".into()));
+        assert_eq!(Answer::Text(" 142".into()), Answer::Unsigned(142));
+
+        assert_eq!(Answer::Unsigned(142), Answer::Signed(142));
+        assert_eq!(Answer::Text("142".into()), Answer::Unsigned(142));
+        assert_eq!(
+            Answer::Text(u128::MAX.to_string()),
+            Answer::Unsigned(u128::MAX)
+        );
+        assert_ne!(Answer::Unsigned(u128::MAX), Answer::Signed(-1));
+        assert_ne!(Answer::Signed(1), Answer::Signed(2));
+        assert_ne!(Answer::Text("a".into()), Answer::Text("b".into()));
+    }
+
+    fn hash(answer: &Answer) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        answer.hash(&mut hasher);
+        hasher.finish()
+    }
+}
@@ -0,0 +1,194 @@
+//! Statistical timing of solutions.
+
+use std::time::Duration;
+
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{runtime, solution::PartSolution, Answer, Day, Part};
+
+/// How many times to run a solution when benchmarking it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Untimed runs before measuring, to warm up caches and the allocator.
+    pub warmup: u32,
+    /// Timed runs.
+    pub runs: u32,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            runs: 10,
+        }
+    }
+}
+
+/// Summary statistics for a set of timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    /// The 95th percentile, using the nearest-rank method.
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarise `samples`, or return `None` if there aren't any.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+        let total: Duration = sorted.iter().sum();
+        let p95_rank = (n * 95).div_ceil(100);
+
+        Some(Self {
+            min: sorted[0],
+            median,
+            mean: total / n as u32,
+            p95: sorted[p95_rank - 1],
+        })
+    }
+}
+
+/// Timings for one part of a day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub day: Day,
+    pub part: Part,
+    /// The number of timed runs.
+    pub runs: u32,
+    /// How long parsing the input took.
+    pub parse: Stats,
+    /// How long solving the part took, not including parsing.
+    pub solve: Stats,
+    /// The answer every run gave.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer: Option<Answer>,
+}
+
+/// Time one part of a solution against `input`.
+///
+/// Checks for cancellation between runs, so it can be stopped through [`runtime`].
+pub fn bench(solution: PartSolution, input: &str, options: BenchOptions) -> Result<BenchResult> {
+    let runs = options.runs.max(1);
+
+    for _ in 0..options.warmup {
+        runtime::check_cancelled()?;
+        solution.run(input)?;
+    }
+
+    let mut parse_times = Vec::with_capacity(runs as usize);
+    let mut solve_times = Vec::with_capacity(runs as usize);
+    let mut answer: Option<Answer> = None;
+    for _ in 0..runs {
+        runtime::check_cancelled()?;
+        let output = solution.run(input)?;
+        parse_times.push(output.parse_time);
+        solve_times.push(output.solve_time);
+        match &answer {
+            Some(previous) if *previous != output.answer => {
+                return Err(eyre!(
+                    "Day {} part {} gave different answers between runs: {previous} and {}",
+                    solution.day,
+                    solution.part,
+                    output.answer
+                ));
+            },
+            _ => answer = Some(output.answer),
+        }
+    }
+
+    Ok(BenchResult {
+        day: solution.day,
+        part: solution.part,
+        runs,
+        parse: Stats::from_samples(&parse_times).unwrap(),
+        solve: Stats::from_samples(&solve_times).unwrap(),
+        answer,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Registry, Solution};
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(Stats::from_samples(&[]), None);
+
+        let samples: Vec<_> = [5, 1, 4, 2, 3].map(ms).to_vec();
+        assert_eq!(
+            Stats::from_samples(&samples),
+            Some(Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3),
+                p95: ms(5),
+            })
+        );
+
+        let samples: Vec<_> = (1..=20).map(ms).collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(stats.median, Duration::from_micros(10_500));
+        assert_eq!(stats.p95, ms(19));
+    }
+
+    struct Lines;
+
+    impl Solution for Lines {
+        type Answer1 = usize;
+        type Answer2 = usize;
+        type Input = Vec<String>;
+
+        const TITLE: &'static str = "Lines";
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            Ok(input.lines().map(String::from).collect())
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+            Ok(input.len())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+            Ok(input.iter().map(String::len).sum())
+        }
+    }
+
+    #[test]
+    fn test_bench() {
+        let registry = Registry::new().register(Day::FIRST, Lines);
+        let options = BenchOptions { warmup: 1, runs: 0 };
+        let result = bench(
+            registry.get(Day::FIRST, Part::Two).unwrap(),
+            "a\nbc",
+            options,
+        )
+        .unwrap();
+        assert_eq!(result.day, Day::FIRST);
+        assert_eq!(result.part, Part::Two);
+        assert_eq!(result.runs, 1);
+        assert_eq!(result.answer, Some(Answer::Unsigned(3)));
+        assert!(result.solve.min <= result.solve.p95);
+    }
+}
@@ -0,0 +1,206 @@
+//! Finding where a repeated simulation starts going round in circles.
+//!
+//! Puzzles like "tilt the platform a billion times" only work because the state
+//! eventually repeats. Once the [`Cycle`] is known, the state after any number
+//! of steps can be found by simulating at most one lap of it.
+//!
+//! Every cycle found is also recorded with the [`runtime`], so the TUI can
+//! show it.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::runtime::{self, Cancelled};
+
+/// A sequence of states that starts repeating after `start` steps, and then
+/// repeats every `period` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first step that is part of the cycle.
+    pub start: usize,
+    /// The length of the cycle. Always at least 1.
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// The state after `n` steps, simulating only as far as the
+    /// [equivalent step](Self::equivalent_step).
+    pub fn state_at<S>(&self, initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+        (0..self.equivalent_step(n)).fold(initial, |state, _| step(&state))
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "starts at step {} and repeats every {} steps",
+            self.start, self.period
+        )
+    }
+}
+
+/// Find the cycle with Brent's algorithm, which only keeps two states in
+/// memory but calls `step` up to three times per state.
+///
+/// Never returns if the states don't repeat, unless the run is cancelled.
+pub fn brent<S: Clone + PartialEq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+) -> Result<Cycle, Cancelled> {
+    // Find the period, by moving the tortoise to the hare at every power of two.
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        runtime::check_cancelled()?;
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Find the start, by moving two states a period apart until they meet.
+    let mut tortoise = initial.clone();
+    let mut hare = (0..period).fold(initial, |state, _| step(&state));
+    let mut start = 0;
+    while tortoise != hare {
+        runtime::check_cancelled()?;
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let cycle = Cycle { start, period };
+    runtime::record_cycle(cycle);
+    Ok(cycle)
+}
+
+/// Find the cycle by remembering a hash of every state, which calls `step`
+/// once per state but keeps a `u64` per state in memory.
+///
+/// States are never compared directly, so this suits states that are large or
+/// expensive to compare. Two different states having the same 64-bit hash is
+/// possible in principle, but vanishingly unlikely.
+///
+/// Never returns if the states don't repeat, unless the run is cancelled.
+pub fn hashed<S: Hash>(initial: S, mut step: impl FnMut(&S) -> S) -> Result<Cycle, Cancelled> {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    for n in 0.. {
+        runtime::check_cancelled()?;
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        if let Some(start) = seen.insert(hasher.finish(), n) {
+            let cycle = Cycle {
+                start,
+                period: n - start,
+            };
+            runtime::record_cycle(cycle);
+            return Ok(cycle);
+        }
+        state = step(&state);
+    }
+    unreachable!("ran out of steps")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::runtime::RunContext;
+
+    /// 3 steps of run-up into a cycle of 7: 0, 1, 2, then 3..=9 forever.
+    fn step(n: &u32) -> u32 {
+        if *n == 9 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let expected = Cycle {
+            start: 3,
+            period: 7,
+        };
+        assert_eq!(brent(0, step), Ok(expected));
+        assert_eq!(hashed(0, step), Ok(expected));
+        assert_eq!(
+            brent(5, step),
+            Ok(Cycle {
+                start: 0,
+                period: 7
+            })
+        );
+        assert_eq!(
+            hashed(4, |_| 4),
+            Ok(Cycle {
+                start: 0,
+                period: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_state_at() {
+        let cycle = brent(0, step).unwrap();
+        assert_eq!(cycle.equivalent_step(2), 2);
+        assert_eq!(cycle.equivalent_step(10), 3);
+        // The billionth state of 3, 4, ..., 9, 3, ... after 0, 1, 2.
+        assert_eq!(
+            cycle.state_at(0, step, 1_000_000_000),
+            3 + (1_000_000_000 - 3) % 7
+        );
+        assert_eq!(
+            cycle.to_string(),
+            "starts at step 3 and repeats every 7 steps"
+        );
+    }
+
+    #[test]
+    fn test_records_and_cancels() {
+        let context = RunContext::new();
+        context.enter(|| {
+            brent(0, step).unwrap();
+            hashed(5, step).unwrap();
+        });
+        assert_eq!(
+            context.notes().cycles,
+            vec![
+                Cycle {
+                    start: 3,
+                    period: 7
+                },
+                Cycle {
+                    start: 0,
+                    period: 7
+                },
+            ]
+        );
+
+        context.cancel();
+        context.enter(|| {
+            // This never repeats, so it would run forever if not cancelled.
+            assert_eq!(hashed(0u64, |n| n + 1), Err(Cancelled));
+            assert_eq!(brent(0u64, |n| n + 1), Err(Cancelled));
+        });
+    }
+}
@@ -0,0 +1,299 @@
+//! Checking solutions against the examples from the puzzle text.
+//!
+//! Examples are stored as `examples/dayNN.toml` inside some base directory,
+//! with one `[[example]]` table per example:
+//!
+//! ```toml
+//! [[example]]
+//! name = "first example"
+//! input = """
+//! 1abc2
+//! pqr3stu8vwx
+//! """
+//! part1 = "142"
+//! ```
+//!
+//! Both answers are optional, as some examples only apply to one part. Inputs
+//! are normalised the same way as real puzzle inputs.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use serde::Deserialize;
+
+use crate::{input::normalize, solution::PartSolution, Answer, Day, Part, Registry};
+
+/// The name of the directory inside the base directory that holds examples.
+pub const EXAMPLES_DIR: &str = "examples";
+
+/// One example input, with the answers the puzzle text gives for it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Example {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub input: String,
+    #[serde(default)]
+    pub part1: Option<String>,
+    #[serde(default)]
+    pub part2: Option<String>,
+}
+
+impl Example {
+    /// The expected answer for `part`, if the example has one.
+    pub fn expected(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ExampleFile {
+    #[serde(default)]
+    example: Vec<Example>,
+}
+
+/// Parse the contents of an examples file.
+pub fn parse(text: &str) -> Result<Vec<Example>> {
+    let file: ExampleFile = toml::from_str(text)?;
+    Ok(file
+        .example
+        .into_iter()
+        .map(|example| Example {
+            input: normalize(&example.input),
+            ..example
+        })
+        .collect())
+}
+
+/// Reads examples from `<base dir>/examples/`.
+#[derive(Debug, Clone)]
+pub struct ExampleStore {
+    dir: PathBuf,
+}
+
+impl ExampleStore {
+    /// Create an example store inside the given base directory.
+    pub fn new(base_dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: base_dir.as_ref().join(EXAMPLES_DIR),
+        }
+    }
+
+    /// The path that a day's examples are (or would be) stored at.
+    pub fn path(&self, day: Day) -> PathBuf {
+        self.dir.join(format!("day{:02}.toml", day.get()))
+    }
+
+    /// Load a day's examples, or none if the day has no examples file.
+    pub fn load(&self, day: Day) -> Result<Vec<Example>> {
+        let path = self.path(day);
+        match fs::read_to_string(&path) {
+            Ok(text) => parse(&text)
+                .wrap_err_with(|| format!("Error parsing examples from {}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => {
+                Err(e).wrap_err_with(|| format!("Error reading examples from {}", path.display()))
+            },
+        }
+    }
+}
+
+/// The result of running one part of a solution against one example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExampleOutcome {
+    pub day: Day,
+    pub part: Part,
+    /// The example's position in its file, starting from 1.
+    pub index: usize,
+    pub name: Option<String>,
+    pub expected: String,
+    /// The solution's answer, or the error it returned.
+    pub actual: Result<Answer, String>,
+}
+
+impl ExampleOutcome {
+    /// Whether the solution gave the expected answer.
+    pub fn passed(&self) -> bool {
+        matches!(&self.actual, Ok(answer) if answer.matches(&self.expected))
+    }
+}
+
+impl fmt::Display for ExampleOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {} part {} example {}",
+            self.day, self.part, self.index
+        )?;
+        if let Some(name) = &self.name {
+            write!(f, " ({name})")?;
+        }
+        match &self.actual {
+            _ if self.passed() => write!(f, ": ok"),
+            Ok(actual) => write!(f, ": expected {}, got {actual}", self.expected),
+            Err(e) => write!(f, ": expected {}, failed with {e}", self.expected),
+        }
+    }
+}
+
+/// Run one part of a solution against every example that has an answer for it.
+pub fn run_examples(solution: PartSolution, examples: &[Example]) -> Vec<ExampleOutcome> {
+    examples
+        .iter()
+        .enumerate()
+        .filter_map(|(i, example)| {
+            let expected = example.expected(solution.part)?;
+            Some(ExampleOutcome {
+                day: solution.day,
+                part: solution.part,
+                index: i + 1,
+                name: example.name.clone(),
+                expected: expected.to_string(),
+                actual: solution
+                    .run(&example.input)
+                    .map(|output| output.answer)
+                    .map_err(|e| format!("{e:#}")),
+            })
+        })
+        .collect()
+}
+
+/// Run every registered solution against its examples.
+pub fn run_all(registry: &Registry, store: &ExampleStore) -> Result<Vec<ExampleOutcome>> {
+    let mut outcomes = Vec::new();
+    for day in registry.days() {
+        let examples = store.load(day)?;
+        for part in Part::ALL {
+            outcomes.extend(run_examples(registry.get(day, part).unwrap(), &examples));
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Run every registered solution against its examples, and panic if any fail.
+///
+/// Meant to be called from a `#[test]`.
+pub fn assert_examples(registry: &Registry, store: &ExampleStore) {
+    let outcomes = run_all(registry, store).unwrap();
+    let failures: Vec<String> = outcomes
+        .iter()
+        .filter(|outcome| !outcome.passed())
+        .map(ToString::to_string)
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{} of {} examples failed:\n{}",
+        failures.len(),
+        outcomes.len(),
+        failures.join("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::Solution;
+
+    /// Sums the numbers on each line.
+    struct Sum;
+
+    impl Solution for Sum {
+        type Answer1 = i64;
+        type Answer2 = usize;
+        type Input = Vec<i64>;
+
+        const TITLE: &'static str = "Sums";
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            Ok(input.lines().map(str::parse).collect::<Result<_, _>>()?)
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+            Ok(input.len())
+        }
+    }
+
+    const EXAMPLES: &str = r#"
+[[example]]
+input = """
+1
+2
+"""
+part1 = "3"
+part2 = "2"
+
+[[example]]
+name = "wrong"
+input = "4"
+part1 = "5"
+
+[[example]]
+input = "x"
+part2 = "1"
+"#;
+
+    #[test]
+    fn test_parse() {
+        let examples = parse(EXAMPLES).unwrap();
+        assert_eq!(examples.len(), 3);
+        assert_eq!(examples[0].input, "1\n2");
+        assert_eq!(examples[1].name.as_deref(), Some("wrong"));
+        assert_eq!(examples[1].expected(Part::Two), None);
+        assert_eq!(parse("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_run_examples() {
+        let registry = Registry::new().register(Day::FIRST, Sum);
+        let examples = parse(EXAMPLES).unwrap();
+
+        let outcomes = run_examples(registry.get(Day::FIRST, Part::One).unwrap(), &examples);
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[0].passed());
+        assert!(!outcomes[1].passed());
+        assert_eq!(
+            outcomes[1].to_string(),
+            "Day 1 part 1 example 2 (wrong): expected 5, got 4"
+        );
+
+        let outcomes = run_examples(registry.get(Day::FIRST, Part::Two).unwrap(), &examples);
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes[0].passed());
+        assert!(outcomes[1].actual.is_err());
+    }
+
+    #[test]
+    fn test_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ExampleStore::new(dir.path());
+        let registry = Registry::new().register(Day::FIRST, Sum);
+        assert_eq!(run_all(&registry, &store).unwrap(), vec![]);
+
+        fs::create_dir_all(dir.path().join(EXAMPLES_DIR)).unwrap();
+        fs::write(store.path(Day::FIRST), EXAMPLES).unwrap();
+        let outcomes = run_all(&registry, &store).unwrap();
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(outcomes.iter().filter(|o| o.passed()).count(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "2 of 4 examples failed")]
+    fn test_assert_examples() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ExampleStore::new(dir.path());
+        fs::create_dir_all(dir.path().join(EXAMPLES_DIR)).unwrap();
+        fs::write(store.path(Day::FIRST), EXAMPLES).unwrap();
+        assert_examples(&Registry::new().register(Day::FIRST, Sum), &store);
+    }
+}
@@ -0,0 +1,636 @@
+//! Points, vectors and directions with signed coordinates.
+//!
+//! A [`Point`] is a position and a [`Vec2`] is a displacement between two, so
+//! `Point + Vec2` is a `Point` and `Point - Point` is a `Vec2`. Grids have `y`
+//! increasing downwards, so [`Direction::Up`] is `(0, -1)`.
+//!
+//! A [`Polygon`] is a closed loop of points, for puzzles that ask how much a
+//! loop encloses. Its areas and point counts use `i128` so that loops with
+//! coordinates anywhere in the range of an `i64` can't overflow.
+
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+use eyre::{eyre, Report, Result};
+
+use crate::math::gcd;
+
+/// A position on a 2D grid, with `y` increasing downwards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// The neighbouring point in `direction`.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+
+    /// The taxicab distance between two points.
+    pub fn manhattan_distance(self, other: Self) -> u64 {
+        (other - self).manhattan_length()
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add<Vec2> for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Vec2) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vec2> for Point {
+    fn add_assign(&mut self, rhs: Vec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vec2> for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Vec2) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign<Vec2> for Point {
+    fn sub_assign(&mut self, rhs: Vec2) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Self) -> Vec2 {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+/// A 2D displacement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Vec2 {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// The taxicab length of the vector.
+    pub fn manhattan_length(self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    pub fn dot(self, rhs: Self) -> i64 {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    /// The z component of the 3D cross product, i.e. twice the signed area of
+    /// the triangle the two vectors span.
+    pub fn cross(self, rhs: Self) -> i64 {
+        self.x * rhs.y - self.y * rhs.x
+    }
+}
+
+/// A 3D position or displacement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub const ZERO: Self = Self::new(0, 0, 0);
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// The taxicab length of the vector.
+    pub fn manhattan_length(self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    pub fn dot(self, rhs: Self) -> i64 {
+        self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+
+    pub fn cross(self, rhs: Self) -> Self {
+        Self::new(
+            self.y * rhs.z - self.z * rhs.y,
+            self.z * rhs.x - self.x * rhs.z,
+            self.x * rhs.y - self.y * rhs.x,
+        )
+    }
+}
+
+/// Implement the arithmetic operators shared by [`Vec2`] and [`Vec3`].
+macro_rules! impl_vector_ops {
+    ($ty:ident { $($field:ident),+ }) => {
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let fields = [$(self.$field),+].map(|n| n.to_string());
+                write!(f, "({})", fields.join(", "))
+            }
+        }
+
+        impl Add for $ty {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl AddAssign for $ty {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl Sub for $ty {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl SubAssign for $ty {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Neg for $ty {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl Mul<i64> for $ty {
+            type Output = Self;
+
+            fn mul(self, rhs: i64) -> Self {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+    };
+}
+
+impl_vector_ops!(Vec2 { x, y });
+impl_vector_ops!(Vec3 { x, y, z });
+
+/// One of the four orthogonal directions on a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// A quarter turn anticlockwise.
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Right => Self::Up,
+            Self::Down => Self::Right,
+            Self::Left => Self::Down,
+        }
+    }
+
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    /// A half turn.
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Right => Self::Left,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+        }
+    }
+
+    /// A step of one cell in this direction.
+    pub fn delta(self) -> Vec2 {
+        match self {
+            Self::Up => Vec2::new(0, -1),
+            Self::Right => Vec2::new(1, 0),
+            Self::Down => Vec2::new(0, 1),
+            Self::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    /// Whether the direction is left or right.
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Self::Up => '^',
+            Self::Right => '>',
+            Self::Down => 'v',
+            Self::Left => '<',
+        };
+        write!(f, "{c}")
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = Report;
+
+    /// Parse an arrow (`^>v<`), a letter (`URDL`) or a compass point (`NESW`).
+    fn try_from(c: char) -> Result<Self, Report> {
+        match c {
+            '^' | 'U' | 'N' => Ok(Self::Up),
+            '>' | 'R' | 'E' => Ok(Self::Right),
+            'v' | 'D' | 'S' => Ok(Self::Down),
+            '<' | 'L' | 'W' => Ok(Self::Left),
+            _ => Err(eyre!("Not a direction: {c:?}")),
+        }
+    }
+}
+
+/// A closed loop through lattice points, which must not cross itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    /// A polygon with edges between consecutive vertices, and from the last
+    /// vertex back to the first.
+    pub fn new(vertices: Vec<Point>) -> Self {
+        Self { vertices }
+    }
+
+    /// Trace a polygon by taking each step in turn from `start`. If the steps
+    /// don't end at `start`, the loop is closed with a straight edge.
+    ///
+    /// Fails if a step goes outside the range of an `i64`.
+    pub fn from_steps(
+        start: Point,
+        steps: impl IntoIterator<Item = (Direction, i64)>,
+    ) -> Result<Self> {
+        let mut vertices = vec![start];
+        let (mut x, mut y) = (i128::from(start.x), i128::from(start.y));
+        for (i, (direction, length)) in steps.into_iter().enumerate() {
+            let delta = direction.delta();
+            x += i128::from(delta.x) * i128::from(length);
+            y += i128::from(delta.y) * i128::from(length);
+            let (Ok(x), Ok(y)) = (i64::try_from(x), i64::try_from(y)) else {
+                return Err(eyre!("Step {} goes outside the range of an i64", i + 1));
+            };
+            vertices.push(Point::new(x, y));
+        }
+        if vertices.len() > 1 && vertices.last() == Some(&start) {
+            vertices.pop();
+        }
+        Ok(Self { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Every edge, as pairs of consecutive vertices.
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// The horizontal and vertical distances along every edge. These are
+    /// computed in `i128`, as an edge can span more than an `i64` can hold.
+    fn edge_spans(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let span = |a: i64, b: i64| {
+            u64::try_from((i128::from(b) - i128::from(a)).unsigned_abs())
+                .expect("the distance between two i64s fits in a u64")
+        };
+        self.edges()
+            .map(move |(a, b)| (span(a.x, b.x), span(a.y, b.y)))
+    }
+
+    /// Twice the signed area, from the shoelace formula. Positive when the
+    /// vertices go clockwise on screen (with `y` increasing downwards).
+    pub fn twice_signed_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| i128::from(a.x) * i128::from(b.y) - i128::from(b.x) * i128::from(a.y))
+            .sum()
+    }
+
+    /// The enclosed area. This is exact for rectilinear loops, but rounded
+    /// down if the loop has diagonal edges.
+    pub fn area(&self) -> i128 {
+        self.twice_signed_area().abs() / 2
+    }
+
+    /// The taxicab length of the loop, which is its perimeter if it's rectilinear.
+    pub fn perimeter(&self) -> i128 {
+        self.edge_spans()
+            .map(|(dx, dy)| i128::from(dx) + i128::from(dy))
+            .sum()
+    }
+
+    /// The number of lattice points on the loop itself.
+    pub fn boundary_points(&self) -> i128 {
+        self.edge_spans()
+            .map(|(dx, dy)| i128::from(gcd(dx, dy)))
+            .sum()
+    }
+
+    /// The number of lattice points strictly inside the loop, from Pick's theorem.
+    ///
+    /// Loops with no area, such as those with fewer than 3 vertices, have none.
+    pub fn interior_points(&self) -> i128 {
+        let twice_area = self.twice_signed_area().abs();
+        if twice_area == 0 {
+            return 0;
+        }
+        // A = I + B / 2 - 1, doubled to keep everything an integer.
+        (twice_area - self.boundary_points()) / 2 + 1
+    }
+
+    /// The number of lattice points inside or on the loop.
+    pub fn enclosed_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{search::Bfs, Grid};
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+        assert_eq!(b - a, Vec2::new(3, -4));
+        assert_eq!(a + (b - a), b);
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.step(Direction::Up), Point::new(1, 1));
+
+        let mut c = a;
+        c += Vec2::new(1, 1) * 3;
+        c -= -Vec2::new(0, 1);
+        assert_eq!(c, Point::new(4, 6));
+    }
+
+    #[test]
+    fn test_vectors() {
+        assert_eq!(Vec2::new(1, 0).cross(Vec2::new(0, 1)), 1);
+        assert_eq!(Vec2::new(2, 3).dot(Vec2::new(4, -1)), 5);
+        assert_eq!(Vec2::new(-3, 4).manhattan_length(), 7);
+
+        let x = Vec3::new(1, 0, 0);
+        let y = Vec3::new(0, 1, 0);
+        assert_eq!(x.cross(y), Vec3::new(0, 0, 1));
+        assert_eq!((x + y * 2 - Vec3::new(0, 0, 3)).to_string(), "(1, 2, -3)");
+        assert_eq!(Vec3::new(1, -2, 3).manhattan_length(), 6);
+        assert_eq!(Vec3::new(1, 2, 3).dot(Vec3::new(4, 5, 6)), 32);
+    }
+
+    #[test]
+    fn test_directions() {
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_right().turn_right(), d.opposite());
+            assert_eq!(d.delta() + d.opposite().delta(), Vec2::ZERO);
+            assert_eq!(
+                Direction::try_from(d.to_string().chars().next().unwrap()).unwrap(),
+                d
+            );
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::try_from('W').unwrap(), Direction::Left);
+        assert!(Direction::try_from('x').is_err());
+        assert!(Direction::Left.is_horizontal());
+    }
+
+    #[test]
+    fn test_polygon_from_steps() {
+        // The dig plan from day 18's example.
+        let plan = "\
+R 6
+D 5
+L 2
+D 2
+R 2
+D 2
+L 5
+U 2
+L 1
+U 2
+R 2
+U 3
+L 2
+U 2";
+        let steps = plan.lines().map(|line| {
+            let (d, n) = line.split_once(' ').unwrap();
+            let d = Direction::try_from(d.chars().next().unwrap()).unwrap();
+            (d, n.parse().unwrap())
+        });
+        let lagoon = Polygon::from_steps(Point::ORIGIN, steps).unwrap();
+        assert_eq!(lagoon.vertices().len(), 14);
+        assert_eq!(lagoon.perimeter(), 38);
+        assert_eq!(lagoon.boundary_points(), 38);
+        assert_eq!(lagoon.area(), 42);
+        assert_eq!(lagoon.interior_points(), 24);
+        assert_eq!(lagoon.enclosed_points(), 62);
+    }
+
+    #[test]
+    fn test_polygon_orientation() {
+        let square = vec![
+            Point::new(0, 0),
+            Point::new(2, 0),
+            Point::new(2, 2),
+            Point::new(0, 2),
+        ];
+        let clockwise = Polygon::new(square.clone());
+        let anticlockwise = Polygon::new(square.into_iter().rev().collect());
+        assert_eq!(clockwise.twice_signed_area(), 8);
+        assert_eq!(anticlockwise.twice_signed_area(), -8);
+        assert_eq!(anticlockwise.interior_points(), 1);
+
+        let triangle = Polygon::new(vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 4)]);
+        assert_eq!(triangle.boundary_points(), 12);
+        assert_eq!(triangle.interior_points(), 3);
+    }
+
+    #[test]
+    fn test_degenerate_polygons() {
+        assert_eq!(Polygon::new(vec![]).interior_points(), 0);
+        assert_eq!(
+            Polygon::from_steps(Point::ORIGIN, [])
+                .unwrap()
+                .interior_points(),
+            0
+        );
+
+        let line = Polygon::from_steps(Point::ORIGIN, [(Direction::Right, 5)]).unwrap();
+        assert_eq!(line.interior_points(), 0);
+    }
+
+    #[test]
+    fn test_polygon_large_coordinates() {
+        let big = 4_000_000_000;
+        let square = Polygon::from_steps(
+            Point::ORIGIN,
+            [
+                (Direction::Right, big),
+                (Direction::Down, big),
+                (Direction::Left, big),
+                (Direction::Up, big),
+            ],
+        )
+        .unwrap();
+        assert_eq!(square.area(), i128::from(big) * i128::from(big));
+    }
+
+    #[test]
+    fn test_polygon_extreme_coordinates() {
+        // The long edges span more than an i64 can hold.
+        let (min, max) = (i64::MIN, i64::MAX);
+        let strip = Polygon::new(vec![
+            Point::new(min, 0),
+            Point::new(max, 0),
+            Point::new(max, 1),
+            Point::new(min, 1),
+        ]);
+        let length = i128::from(max) - i128::from(min);
+        assert_eq!(strip.perimeter(), 2 * length + 2);
+        assert_eq!(strip.boundary_points(), 2 * length + 2);
+        assert_eq!(strip.area(), length);
+        assert_eq!(strip.interior_points(), 0);
+
+        let error = Polygon::from_steps(
+            Point::new(max - 1, 0),
+            [(Direction::Left, 5), (Direction::Right, 10)],
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Step 2 goes outside the range of an i64");
+    }
+
+    /// A loop around columns of cells, where each column spans `bottom..top`
+    /// and overlaps its neighbours, so the loop never touches itself.
+    fn columns() -> impl Strategy<Value = Vec<(i64, i64)>> {
+        prop::collection::vec((0i64..6, 0i64..4), 1..6).prop_map(|raw| {
+            let mut columns: Vec<(i64, i64)> = Vec::new();
+            for (bottom, len) in raw {
+                let (bottom, top) = match columns.last() {
+                    Some(&(prev_bottom, prev_top)) => {
+                        let bottom = bottom.min(prev_top - 1);
+                        (bottom, (bottom + 1 + len).max(prev_bottom + 1))
+                    },
+                    None => (bottom, bottom + 1 + len),
+                };
+                columns.push((bottom, top));
+            }
+            columns
+        })
+    }
+
+    fn column_polygon(columns: &[(i64, i64)]) -> Polygon {
+        let mut vertices = Vec::new();
+        for (x, &(bottom, _)) in columns.iter().enumerate() {
+            vertices.push(Point::new(x as i64, bottom));
+            vertices.push(Point::new(x as i64 + 1, bottom));
+        }
+        for (x, &(_, top)) in columns.iter().enumerate().rev() {
+            vertices.push(Point::new(x as i64 + 1, top));
+            vertices.push(Point::new(x as i64, top));
+        }
+        Polygon::new(vertices)
+    }
+
+    /// Count the lattice points inside and on a loop by flood filling the
+    /// outside, with a margin of one so that the outside is connected.
+    fn flood_fill(polygon: &Polygon) -> (usize, usize) {
+        let max_x = polygon.vertices().iter().map(|p| p.x).max().unwrap();
+        let max_y = polygon.vertices().iter().map(|p| p.y).max().unwrap();
+        let offset = Vec2::new(1, 1);
+        let mut grid = Grid::filled(max_x as usize + 3, max_y as usize + 3, false);
+        for (a, b) in polygon.edges() {
+            let direction = Direction::ALL
+                .into_iter()
+                .find(|d| d.delta() * (b - a).manhattan_length() as i64 == b - a);
+            let mut p = a;
+            grid[p + offset] = true;
+            while p != b {
+                p = p.step(direction.unwrap());
+                grid[p + offset] = true;
+            }
+        }
+        let boundary = grid.iter().filter(|(_, &on_loop)| on_loop).count();
+        let outside = Bfs::new(Point::ORIGIN, |&p: &Point| {
+            grid.neighbours4(p)
+                .filter(|&n| !grid[n])
+                .collect::<Vec<_>>()
+        })
+        .count();
+        let total = grid.width() * grid.height();
+        (total - outside - boundary, boundary)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_polygon_matches_flood_fill(columns in columns(), reverse: bool) {
+            let mut polygon = column_polygon(&columns);
+            if reverse {
+                polygon.vertices.reverse();
+            }
+            let (interior, boundary) = flood_fill(&polygon);
+            prop_assert_eq!(polygon.boundary_points(), boundary as i128);
+            prop_assert_eq!(polygon.perimeter(), boundary as i128);
+            prop_assert_eq!(polygon.interior_points(), interior as i128);
+
+            let cells: i64 = columns.iter().map(|(bottom, top)| top - bottom).sum();
+            prop_assert_eq!(polygon.area(), i128::from(cells));
+        }
+    }
+}
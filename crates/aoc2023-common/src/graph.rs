@@ -0,0 +1,474 @@
+//! Undirected graphs with named nodes, and ways of cutting them in two.
+//!
+//! Node names are interned as they're added, so algorithms work with dense
+//! [`NodeId`]s and only look names up again for display. Parallel edges are
+//! merged into a single edge with a weight.
+
+use std::{
+    collections::{BTreeMap, BinaryHeap, HashMap},
+    str::FromStr,
+};
+
+use eyre::{eyre, Report, Result};
+use rand::Rng;
+
+/// A node's index in its [`Graph`].
+pub type NodeId = usize;
+
+/// An undirected, weighted graph with named nodes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    /// The weight of the edge to each neighbour.
+    adjacency: Vec<BTreeMap<NodeId, u64>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse lines like `jqt: rhn xhk nvd`, each connecting the node before
+    /// the colon to every node after it.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut graph = Self::new();
+        for line in input.lines() {
+            let (node, neighbours) = line
+                .split_once(':')
+                .ok_or_else(|| eyre!("Expected a line like \"a: b c\", found {line:?}"))?;
+            let node = node.trim();
+            if node.is_empty() {
+                return Err(eyre!("Missing node name in {line:?}"));
+            }
+            let a = graph.intern(node);
+            for neighbour in neighbours.split_whitespace() {
+                let b = graph.intern(neighbour);
+                graph.add_edge(a, b, 1);
+            }
+        }
+        Ok(graph)
+    }
+
+    /// The ID of the node called `name`, adding it if it's new.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.adjacency.push(BTreeMap::new());
+        id
+    }
+
+    /// The ID of the node called `name`, if there is one.
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Connect two nodes, adding to the weight of any existing edge between them.
+    /// Self-loops are ignored, since they never cross a cut.
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId, weight: u64) {
+        if a != b {
+            *self.adjacency[a].entry(b).or_default() += weight;
+            *self.adjacency[b].entry(a).or_default() += weight;
+        }
+    }
+
+    /// Remove the edge between two nodes, returning its weight if it existed.
+    pub fn remove_edge(&mut self, a: NodeId, b: NodeId) -> Option<u64> {
+        self.adjacency[b].remove(&a);
+        self.adjacency[a].remove(&b)
+    }
+
+    /// Every neighbour of `id`, with the weight of the edge to it.
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = (NodeId, u64)> + '_ {
+        self.adjacency[id].iter().map(|(&n, &w)| (n, w))
+    }
+
+    /// Every edge once, as `(a, b, weight)` with `a < b`.
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId, u64)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(a, neighbours)| {
+                neighbours
+                    .range(a + 1..)
+                    .map(move |(&b, &weight)| (a, b, weight))
+            })
+    }
+
+    /// The size of each connected component, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut seen = vec![false; self.len()];
+        let mut sizes = Vec::new();
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut stack = vec![start];
+            let mut size = 0;
+            while let Some(node) = stack.pop() {
+                size += 1;
+                for (next, _) in self.neighbours(node) {
+                    if !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// The cut that separates `side` from every other node.
+    fn cut(&self, mut side: Vec<NodeId>) -> Cut {
+        side.sort_unstable();
+        let mut in_side = vec![false; self.len()];
+        for &node in &side {
+            in_side[node] = true;
+        }
+        let edges: Vec<_> = self
+            .edges()
+            .filter(|&(a, b, _)| in_side[a] != in_side[b])
+            .map(|(a, b, _)| (a, b))
+            .collect();
+        let weight = edges.iter().map(|&(a, b)| self.adjacency[a][&b]).sum();
+        Cut {
+            weight,
+            other_side: self.len() - side.len(),
+            side,
+            edges,
+        }
+    }
+
+    /// The global minimum cut, found with the Stoer–Wagner algorithm, or
+    /// `None` if there are fewer than two nodes.
+    pub fn min_cut(&self) -> Option<Cut> {
+        if self.len() < 2 {
+            return None;
+        }
+
+        // Nodes get merged together, so each remaining node stands for several
+        // original ones.
+        let mut adjacency: Vec<HashMap<NodeId, u64>> = self
+            .adjacency
+            .iter()
+            .map(|neighbours| neighbours.iter().map(|(&n, &w)| (n, w)).collect())
+            .collect();
+        let mut members: Vec<Vec<NodeId>> = (0..self.len()).map(|id| vec![id]).collect();
+        let mut alive: Vec<NodeId> = (0..self.len()).collect();
+        let mut best: Option<(u64, Vec<NodeId>)> = None;
+
+        while alive.len() > 1 {
+            // Add nodes in order of how strongly they're connected to those
+            // already added. The last two are then the "s" and "t" of the phase.
+            let mut connection = vec![0u64; self.len()];
+            let mut added = vec![false; self.len()];
+            let mut heap = BinaryHeap::from([(0, alive[0])]);
+            let (mut s, mut t, mut cut_of_phase, mut count) = (alive[0], alive[0], 0, 0);
+            while let Some((weight, node)) = heap.pop() {
+                if added[node] || weight != connection[node] {
+                    continue;
+                }
+                added[node] = true;
+                count += 1;
+                (s, t, cut_of_phase) = (t, node, weight);
+                for (&next, &w) in &adjacency[node] {
+                    if !added[next] {
+                        connection[next] += w;
+                        heap.push((connection[next], next));
+                    }
+                }
+            }
+
+            if count < alive.len() {
+                // The graph is disconnected, so what we reached is a free cut.
+                let side = alive
+                    .iter()
+                    .filter(|&&node| added[node])
+                    .flat_map(|&node| members[node].iter().copied())
+                    .collect();
+                return Some(self.cut(side));
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(weight, _)| cut_of_phase < *weight)
+            {
+                best = Some((cut_of_phase, members[t].clone()));
+            }
+
+            // Merge t into s.
+            let t_edges = std::mem::take(&mut adjacency[t]);
+            for (next, w) in t_edges {
+                adjacency[next].remove(&t);
+                if next != s {
+                    *adjacency[s].entry(next).or_default() += w;
+                    *adjacency[next].entry(s).or_default() += w;
+                }
+            }
+            let t_members = std::mem::take(&mut members[t]);
+            members[s].extend(t_members);
+            alive.retain(|&node| node != t);
+        }
+
+        best.map(|(_, side)| self.cut(side))
+    }
+
+    /// The smallest cut found by `attempts` runs of Karger's randomised
+    /// contraction algorithm, or `None` if there are fewer than two nodes.
+    /// There's always at least one run, even if `attempts` is zero.
+    ///
+    /// Each run finds the minimum cut with probability at least `2 / n²`, so
+    /// this is only a fallback for checking [`min_cut`](Self::min_cut). Pass a
+    /// seeded `rng` for reproducible results.
+    pub fn karger(&self, rng: &mut impl Rng, attempts: usize) -> Option<Cut> {
+        if self.len() < 2 {
+            return None;
+        }
+        let mut edges: Vec<(f64, NodeId, NodeId, u64)> = self
+            .edges()
+            .map(|(a, b, weight)| (0.0, a, b, weight))
+            .collect();
+
+        let mut best: Option<Cut> = None;
+        for _ in 0..attempts.max(1) {
+            // Contracting edges in order of exponentially distributed keys,
+            // with rates given by their weights, picks each next edge with
+            // probability proportional to its weight, without having to repeat
+            // each edge once per unit of weight.
+            for (key, _, _, weight) in &mut edges {
+                let uniform: f64 = rng.gen();
+                *key = if *weight == 0 {
+                    f64::INFINITY
+                } else {
+                    -(1.0 - uniform).ln() / *weight as f64
+                };
+            }
+            edges.sort_by(|x, y| x.0.total_cmp(&y.0));
+            let mut sets = DisjointSets::new(self.len());
+            let mut components = self.len();
+            for &(_, a, b, _) in &edges {
+                if components == 2 {
+                    break;
+                }
+                if sets.union(a, b) {
+                    components -= 1;
+                }
+            }
+            let root = sets.find(0);
+            let side = (0..self.len()).filter(|&n| sets.find(n) == root).collect();
+            let cut = self.cut(side);
+            if best.as_ref().is_none_or(|best| cut.weight < best.weight) {
+                best = Some(cut);
+            }
+        }
+        best
+    }
+}
+
+impl FromStr for Graph {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// A split of a graph's nodes into two non-empty sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    /// The total weight of the edges between the two sides.
+    pub weight: u64,
+    /// The nodes on one side of the cut, in order.
+    pub side: Vec<NodeId>,
+    /// The number of nodes on the other side.
+    pub other_side: usize,
+    /// The edges between the two sides, as `(a, b)` with `a < b`.
+    pub edges: Vec<(NodeId, NodeId)>,
+}
+
+impl Cut {
+    /// The number of nodes on each side.
+    pub fn sizes(&self) -> (usize, usize) {
+        (self.side.len(), self.other_side)
+    }
+}
+
+/// Union-find over node IDs, with path halving and union by size.
+struct DisjointSets {
+    parent: Vec<NodeId>,
+    size: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut node: NodeId) -> NodeId {
+        while self.parent[node] != node {
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
+
+    /// Merge the sets containing `a` and `b`, returning whether they were separate.
+    fn union(&mut self, a: NodeId, b: NodeId) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            (a, b) = (b, a);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    const EXAMPLE: &str = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    fn edge_names(graph: &Graph, cut: &Cut) -> Vec<(String, String)> {
+        let mut names: Vec<_> = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let (a, b) = (graph.name(a), graph.name(b));
+                (a.min(b).to_string(), a.max(b).to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn expected_edges() -> Vec<(String, String)> {
+        [("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .to_vec()
+    }
+
+    #[test]
+    fn test_parse() {
+        let graph: Graph = EXAMPLE.parse().unwrap();
+        assert_eq!(graph.len(), 15);
+        assert_eq!(graph.edges().count(), 33);
+        assert_eq!(graph.name(graph.id("cmg").unwrap()), "cmg");
+        assert_eq!(graph.id("abc"), None);
+        assert_eq!(graph.component_sizes(), vec![15]);
+
+        assert!(Graph::parse("abc def").is_err());
+        assert!(Graph::parse(": def").is_err());
+    }
+
+    #[test]
+    fn test_min_cut() {
+        let graph = Graph::parse(EXAMPLE).unwrap();
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(edge_names(&graph, &cut), expected_edges());
+        let (a, b) = cut.sizes();
+        assert_eq!(a * b, 54);
+
+        let mut split = graph.clone();
+        for &(a, b) in &cut.edges {
+            assert_eq!(split.remove_edge(a, b), Some(1));
+        }
+        assert_eq!(split.component_sizes(), vec![9, 6]);
+    }
+
+    #[test]
+    fn test_min_cut_edge_cases() {
+        assert_eq!(Graph::parse("a:").unwrap().min_cut(), None);
+
+        // Disconnected graphs can be split without cutting anything.
+        let graph = Graph::parse("a: b\nc: d e").unwrap();
+        let cut = graph.min_cut().unwrap();
+        assert_eq!((cut.weight, cut.sizes()), (0, (2, 3)));
+        assert_eq!(graph.component_sizes(), vec![3, 2]);
+
+        // Parallel edges add up.
+        let graph = Graph::parse("a: b c\nb: a\nc: b").unwrap();
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 2);
+        assert_eq!(cut.side, vec![graph.id("c").unwrap()]);
+    }
+
+    #[test]
+    fn test_karger() {
+        let graph = Graph::parse(EXAMPLE).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        let cut = graph.karger(&mut rng, 100).unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(edge_names(&graph, &cut), expected_edges());
+
+        // The same seed always gives the same cut.
+        let again = graph
+            .karger(&mut ChaCha8Rng::seed_from_u64(25), 100)
+            .unwrap();
+        assert_eq!(again, cut);
+
+        // Asking for no attempts still makes one.
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        assert!(graph.karger(&mut rng, 0).is_some());
+    }
+
+    #[test]
+    fn test_karger_heavy_edges() {
+        // Far too heavy to repeat each edge once per unit of weight.
+        let mut graph = Graph::new();
+        let [a, b, c, d] = ["a", "b", "c", "d"].map(|name| graph.intern(name));
+        graph.add_edge(a, b, u64::MAX / 4);
+        graph.add_edge(b, c, u64::MAX / 4);
+        graph.add_edge(c, a, u64::MAX / 4);
+        graph.add_edge(c, d, 1);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        let cut = graph.karger(&mut rng, 10).unwrap();
+        assert_eq!(cut.weight, 1);
+        assert_eq!(cut.edges, vec![(c.min(d), c.max(d))]);
+    }
+}
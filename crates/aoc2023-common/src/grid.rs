@@ -0,0 +1,481 @@
+//! A dense, rectangular 2D grid.
+//!
+//! Grids are indexed by [`Point`], with `(0, 0)` in the top left corner and `y`
+//! increasing downwards, matching how puzzle inputs are laid out. Lookups
+//! outside the grid return `None` rather than wrapping or panicking, so
+//! neighbours at the edges need no special handling.
+
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use eyre::{eyre, Result};
+
+use crate::geometry::{Direction, Point, Vec2};
+
+/// Offsets to all eight neighbours, clockwise from up.
+const ALL_NEIGHBOURS: [Vec2; 8] = [
+    Vec2::new(0, -1),
+    Vec2::new(1, -1),
+    Vec2::new(1, 0),
+    Vec2::new(1, 1),
+    Vec2::new(0, 1),
+    Vec2::new(-1, 1),
+    Vec2::new(-1, 0),
+    Vec2::new(-1, -1),
+];
+
+/// A rectangular grid of cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid from its cells, listed row by row.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self> {
+        let size = width
+            .checked_mul(height)
+            .ok_or_else(|| eyre!("A {width}x{height} grid is too big"))?;
+        if cells.len() != size {
+            return Err(eyre!(
+                "A {width}x{height} grid needs {size} cells, but {} were given",
+                cells.len()
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Create a grid by calling `f` with the position of each cell.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x as i64, y as i64)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Create a grid with every cell set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parse a grid with one cell per character, converting each with `f`.
+    ///
+    /// Every line must be the same length.
+    pub fn parse_with(input: &str, mut f: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(input.len());
+        for (y, line) in input.lines().enumerate() {
+            let start = cells.len();
+            for (x, c) in line.chars().enumerate() {
+                cells.push(f(c).map_err(|e| e.wrap_err(format!("At ({x}, {y})")))?);
+            }
+            let line_width = cells.len() - start;
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(eyre!(
+                        "Line {} is {line_width} characters long, but the lines before it are {width}",
+                        y + 1
+                    ));
+                },
+                Some(_) => {},
+            }
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether `p` is inside the grid.
+    pub fn contains(&self, p: Point) -> bool {
+        (0..self.width as i64).contains(&p.x) && (0..self.height as i64).contains(&p.y)
+    }
+
+    fn offset(&self, p: Point) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    /// The cell at `p`, or `None` if it's outside the grid.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    /// The cell at `p`, or `None` if it's outside the grid.
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.offset(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x as i64, y as i64)))
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// The position of the first cell, row by row, that matches `predicate`.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(p, _)| p)
+    }
+
+    /// The orthogonal neighbours of `p` that are inside the grid, clockwise from up.
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL
+            .iter()
+            .map(move |&d| p.step(d))
+            .filter(|&n| self.contains(n))
+    }
+
+    /// All eight neighbours of `p` that are inside the grid, clockwise from up.
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        ALL_NEIGHBOURS
+            .iter()
+            .map(move |&d| p + d)
+            .filter(|&n| self.contains(n))
+    }
+
+    /// Row `y` of the grid.
+    ///
+    /// # Panics
+    ///
+    /// If `y` is outside the grid.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Every row of the grid, from top to bottom.
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        // `chunks_exact` would yield nothing for zero-width grids, so go by row number.
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Column `x` of the grid, from top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// If `x` is outside the grid.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        assert!(
+            x < self.width,
+            "column {x} is outside a grid {} wide",
+            self.width
+        );
+        // Grids with no rows have no cells to start the column from.
+        self.cells
+            .get(x..)
+            .unwrap_or_default()
+            .iter()
+            .step_by(self.width)
+    }
+
+    /// Every column of the grid, from left to right.
+    pub fn columns(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T>> + ExactSizeIterator
+    {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Convert every cell with `f`.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Flip the grid over its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, p.x)].clone()
+        })
+    }
+
+    /// Rotate the grid a quarter turn clockwise.
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height as i64;
+        Grid::from_fn(self.height, self.width, |p| {
+            self[Point::new(p.y, height - 1 - p.x)].clone()
+        })
+    }
+
+    /// Rotate the grid a quarter turn anticlockwise.
+    pub fn rotate_anticlockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width as i64;
+        Grid::from_fn(self.height, self.width, |p| {
+            self[Point::new(width - 1 - p.y, p.x)].clone()
+        })
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with(s, Ok)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        match self.get(p) {
+            Some(cell) => cell,
+            None => panic!("{p} is outside a {}x{} grid", self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(p) {
+            Some(cell) => cell,
+            None => panic!("{p} is outside a {width}x{height} grid"),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ratatui")]
+mod widget {
+    use std::fmt::Display;
+
+    use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+
+    use super::*;
+
+    type StyleFn<'a, T> = Box<dyn Fn(Point, &T) -> Style + 'a>;
+
+    /// Draws a [`Grid`] with one terminal cell per grid cell, clipped to the area.
+    pub struct GridWidget<'a, T> {
+        grid: &'a Grid<T>,
+        style: StyleFn<'a, T>,
+    }
+
+    impl<T> Grid<T> {
+        /// A widget that draws this grid, using each cell's [`Display`] output.
+        pub fn widget(&self) -> GridWidget<'_, T> {
+            GridWidget {
+                grid: self,
+                style: Box::new(|_, _| Style::default()),
+            }
+        }
+    }
+
+    impl<'a, T> GridWidget<'a, T> {
+        /// Style each cell based on its position and value.
+        pub fn style(mut self, style: impl Fn(Point, &T) -> Style + 'a) -> Self {
+            self.style = Box::new(style);
+            self
+        }
+    }
+
+    impl<T: Display> Widget for GridWidget<'_, T> {
+        fn render(self, area: Rect, buf: &mut Buffer) {
+            for (p, cell) in self.grid.iter() {
+                if p.x >= area.width as i64 || p.y >= area.height as i64 {
+                    continue;
+                }
+                let (x, y) = (area.x + p.x as u16, area.y + p.y as u16);
+                buf.get_mut(x, y)
+                    .set_symbol(&cell.to_string())
+                    .set_style((self.style)(p, cell));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ratatui")]
+pub use widget::GridWidget;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const INPUT: &str = "abc\ndef";
+
+    fn grid() -> Grid<char> {
+        INPUT.parse().unwrap()
+    }
+
+    fn p(x: i64, y: i64) -> Point {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[p(2, 1)], 'f');
+        assert_eq!(grid.to_string(), INPUT);
+
+        assert!("ab\nc".parse::<Grid<char>>().is_err());
+        let digits = Grid::parse_with("12\n34", |c| {
+            c.to_digit(10).ok_or_else(|| eyre!("Not a digit: {c}"))
+        })
+        .unwrap();
+        assert_eq!(digits.row(1), &[3, 4]);
+        assert!(Grid::parse_with("1x", |c| c
+            .to_digit(10)
+            .ok_or_else(|| eyre!("Not a digit: {c}")))
+        .is_err());
+
+        let empty: Grid<char> = "".parse().unwrap();
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = grid();
+        assert_eq!(grid.get(p(-1, 0)), None);
+        assert_eq!(grid.get(p(3, 0)), None);
+        assert_eq!(grid.get(p(0, 2)), None);
+        *grid.get_mut(p(0, 0)).unwrap() = 'z';
+        grid[p(1, 1)] = 'y';
+        assert_eq!(grid.to_string(), "zbc\ndyf");
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) is outside a 3x2 grid")]
+    fn test_index_out_of_bounds() {
+        let _ = grid()[p(3, 0)];
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours4(p(0, 0)).collect::<Vec<_>>(),
+            vec![p(1, 0), p(0, 1)]
+        );
+        assert_eq!(
+            grid.neighbours8(p(1, 0)).collect::<Vec<_>>(),
+            vec![p(2, 0), p(2, 1), p(1, 1), p(0, 1), p(0, 0)]
+        );
+        assert_eq!(grid.neighbours8(p(1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = grid();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&['a', 'b', 'c'], &['d', 'e', 'f']]
+        );
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["ad", "be", "cf"]
+        );
+        assert_eq!(grid.position(|&c| c == 'e'), Some(p(1, 1)));
+        assert_eq!(
+            grid.iter().map(|(p, _)| p).collect::<Vec<_>>(),
+            grid.points().collect::<Vec<_>>()
+        );
+
+        let no_rows = Grid::<char>::new(3, 0, Vec::new()).unwrap();
+        assert_eq!(no_rows.column(2).count(), 0);
+        assert_eq!(no_rows.columns().map(Iterator::count).sum::<usize>(), 0);
+    }
+
+    #[test]
+    fn test_new_checks_size() {
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+        assert_eq!(
+            Grid::new(usize::MAX, 2, vec![0u8]).unwrap_err().to_string(),
+            format!("A {}x2 grid is too big", usize::MAX)
+        );
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+        assert_eq!(
+            grid.map(|c| c.is_ascii_lowercase()),
+            Grid::filled(3, 2, true)
+        );
+    }
+
+    #[cfg(feature = "ratatui")]
+    #[test]
+    fn test_widget() {
+        use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 3));
+        grid().widget().render(buf.area, &mut buf);
+        assert_eq!(buf, Buffer::with_lines(vec!["ab", "de", "  "]));
+    }
+}
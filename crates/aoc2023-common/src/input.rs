@@ -0,0 +1,198 @@
+//! Loading puzzle inputs from the app's data directory.
+//!
+//! Inputs are stored as `inputs/dayNN.txt` inside the data directory. Every
+//! input is normalised on load, so solutions never have to care about line
+//! endings or trailing newlines.
+
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use eyre::{eyre, Result, WrapErr};
+use sha2::{Digest, Sha256};
+
+use crate::Day;
+
+/// The name of the directory inside the data directory that holds puzzle inputs.
+pub const INPUTS_DIR: &str = "inputs";
+
+/// A loaded, normalised puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleInput {
+    /// The normalised text of the input.
+    pub text: String,
+    /// Hex-encoded SHA-256 hash of the normalised text.
+    pub hash: String,
+    /// The file the input was loaded from.
+    pub path: PathBuf,
+}
+
+impl PuzzleInput {
+    /// Normalise `raw` and hash it, remembering that it came from `path`.
+    pub fn new(raw: &str, path: impl Into<PathBuf>) -> Self {
+        let text = normalize(raw);
+        let hash = hash(&text);
+        Self {
+            text,
+            hash,
+            path: path.into(),
+        }
+    }
+
+    /// Load and normalise an input from an arbitrary file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let raw = fs::read_to_string(path)
+            .wrap_err_with(|| format!("Error reading puzzle input from {}", path.display()))?;
+        Ok(Self::new(&raw, path))
+    }
+
+    /// A short prefix of [`PuzzleInput::hash`], for display.
+    pub fn short_hash(&self) -> &str {
+        self.hash.get(..12).unwrap_or(&self.hash)
+    }
+}
+
+/// Convert CRLF line endings to LF, and strip any trailing newlines, including
+/// a lone trailing `\r`.
+pub fn normalize(raw: &str) -> String {
+    let mut text = raw.replace("\r\n", "\n");
+    let trimmed_len = text.trim_end_matches(['\n', '\r']).len();
+    text.truncate(trimmed_len);
+    text
+}
+
+/// Hex-encoded SHA-256 hash of `text`.
+pub fn hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Reads and writes puzzle inputs in `<data dir>/inputs/`.
+#[derive(Debug, Clone)]
+pub struct InputStore {
+    dir: PathBuf,
+}
+
+impl InputStore {
+    /// Create an input store inside the given data directory.
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: data_dir.as_ref().join(INPUTS_DIR),
+        }
+    }
+
+    /// The directory that inputs are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The path that a day's input is (or would be) stored at.
+    pub fn path(&self, day: Day) -> PathBuf {
+        self.dir.join(format!("day{:02}.txt", day.get()))
+    }
+
+    /// Check if a day's input has been stored.
+    pub fn contains(&self, day: Day) -> bool {
+        self.path(day).is_file()
+    }
+
+    /// Load a day's input.
+    pub fn load(&self, day: Day) -> Result<PuzzleInput> {
+        let path = self.path(day);
+        match fs::read_to_string(&path) {
+            Ok(raw) => Ok(PuzzleInput::new(&raw, path)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(eyre!(
+                "No puzzle input for day {day} at {}. Download it from \
+                 https://adventofcode.com/2023/day/{day}/input and save it there.",
+                path.display()
+            )),
+            Err(e) => Err(e).wrap_err_with(|| {
+                format!(
+                    "Error reading puzzle input for day {day} from {}",
+                    path.display()
+                )
+            }),
+        }
+    }
+
+    /// Save a day's input, overwriting any existing input for that day.
+    pub fn save(&self, day: Day, raw: &str) -> Result<PuzzleInput> {
+        let path = self.path(day);
+        fs::create_dir_all(&self.dir)
+            .wrap_err_with(|| format!("Error creating input directory {}", self.dir.display()))?;
+
+        let input = PuzzleInput::new(raw, path);
+        fs::write(&input.path, &input.text).wrap_err_with(|| {
+            format!(
+                "Error saving puzzle input for day {day} to {}",
+                input.path.display()
+            )
+        })?;
+
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\r\nb\r\n\r\n"), "a\nb");
+        assert_eq!(normalize("  a\nb  \n"), "  a\nb  ");
+        assert_eq!(normalize("\n\n"), "");
+        assert_eq!(normalize("a\nb\r"), "a\nb");
+        assert_eq!(normalize("a\r\n\r"), "a");
+    }
+
+    #[test]
+    fn test_hash_ignores_line_endings() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = PuzzleInput::new("1\r\n2\r\n", dir.path().join("a"));
+        let b = PuzzleInput::new("1\n2", dir.path().join("b"));
+        assert_eq!(a.hash, b.hash);
+        assert_eq!(a.hash.len(), 64);
+        assert_eq!(a.short_hash().len(), 12);
+
+        // The hash is public, so might not be a full SHA-256 hash.
+        let short = PuzzleInput {
+            hash: "abc".to_string(),
+            ..a
+        };
+        assert_eq!(short.short_hash(), "abc");
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = InputStore::new(dir.path());
+        let day = Day::new(4).unwrap();
+
+        assert!(!store.contains(day));
+        assert_eq!(store.path(day), dir.path().join("inputs").join("day04.txt"));
+
+        let saved = store.save(day, "x\r\ny\n").unwrap();
+        assert!(store.contains(day));
+        assert_eq!(store.load(day).unwrap(), saved);
+        assert_eq!(saved.text, "x\ny");
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = InputStore::new(dir.path());
+        let err = store.load(Day::new(12).unwrap()).unwrap_err().to_string();
+        assert!(err.contains("No puzzle input for day 12"), "{err}");
+        assert!(err.contains("day12.txt"), "{err}");
+    }
+}
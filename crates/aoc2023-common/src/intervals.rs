@@ -0,0 +1,296 @@
+//! Sets of integers stored as sorted, disjoint half-open ranges.
+//!
+//! Puzzles like day 5's seed maps push ranges with billions of values through
+//! a series of transformations, so the values can't be handled one at a time.
+//! An [`IntervalSet`] splits, intersects and offsets whole ranges instead.
+
+use std::{
+    fmt,
+    ops::{Add, Range, Sub},
+};
+
+/// A value that can bound an interval. [`Default`] is used as zero.
+pub trait Endpoint: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>> Endpoint for T {}
+
+/// A set of values, stored as sorted, non-overlapping and non-adjacent ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T = i64> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Endpoint> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Endpoint> IntervalSet<T> {
+    /// The empty set.
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Sort and merge arbitrary ranges, dropping empty ones.
+    fn normalize(mut ranges: Vec<Range<T>>) -> Self {
+        ranges.retain(|r| r.start < r.end);
+        ranges.sort_unstable_by_key(|r| r.start);
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        Self { ranges: merged }
+    }
+
+    /// The disjoint ranges that make up the set, in ascending order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of values in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::default(), |total, r| total + (r.end - r.start))
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // The last range starting at or before `value` is the only one that can contain it.
+        let i = self.ranges.partition_point(|r| r.start <= value);
+        i > 0 && value < self.ranges[i - 1].end
+    }
+
+    /// Add a range of values to the set.
+    pub fn insert(&mut self, range: Range<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+        *self = Self::normalize(ranges);
+    }
+
+    /// Every value in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.ranges.iter().chain(&other.ranges).cloned().collect())
+    }
+
+    /// Every value in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            // Whichever range ends first can't overlap anything else in the other set.
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Every value in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for a in &self.ranges {
+            while other.ranges.get(j).is_some_and(|b| b.end <= a.start) {
+                j += 1;
+            }
+            let mut start = a.start;
+            for b in other.ranges[j..].iter().take_while(|b| b.start < a.end) {
+                if start < b.start {
+                    ranges.push(start..b.start);
+                }
+                start = start.max(b.end);
+            }
+            if start < a.end {
+                ranges.push(start..a.end);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Add `offset` to every value in the set.
+    pub fn shift(&self, offset: T) -> Self {
+        Self {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|r| r.start + offset..r.end + offset)
+                .collect(),
+        }
+    }
+
+    /// Map the set through `(source, dest, len)` rules, each of which moves the
+    /// values in `source..source + len` to start at `dest`. Values that no rule
+    /// covers are left where they are, and if rules overlap the first one wins.
+    pub fn map(&self, rules: impl IntoIterator<Item = (T, T, T)>) -> Self {
+        let mut unmapped = self.clone();
+        let mut mapped = Vec::new();
+        for (source, dest, len) in rules {
+            let covered = Self::from(source..source + len);
+            let moved = unmapped.intersection(&covered);
+            if moved.is_empty() {
+                continue;
+            }
+            mapped.extend(moved.shift(dest - source).ranges);
+            unmapped = unmapped.difference(&covered);
+        }
+        mapped.extend(unmapped.ranges);
+        Self::normalize(mapped)
+    }
+}
+
+impl<T: Endpoint> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::normalize(vec![range])
+    }
+}
+
+impl<T: Endpoint> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalize(iter.into_iter().collect())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for IntervalSet<T> {
+    /// Formats like `{[1, 3), [5, 8)}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, r) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "[{}, {})", r.start, r.end)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn set(ranges: &[Range<i64>]) -> IntervalSet {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        let s = set(&[5..8, 1..3, 2..4, 8..9, 10..10]);
+        assert_eq!(s.ranges(), &[1..4, 5..9]);
+        assert_eq!(s.len(), 7);
+        assert_eq!(s.to_string(), "{[1, 4), [5, 9)}");
+        assert!(s.contains(8));
+        assert!(!s.contains(4));
+        assert!(!s.contains(0));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = IntervalSet::from(5..25);
+        assert_eq!(a.union(&b), IntervalSet::from(0..30));
+        assert_eq!(a.intersection(&b), set(&[5..10, 20..25]));
+        assert_eq!(a.difference(&b), set(&[0..5, 25..30]));
+        assert_eq!(b.difference(&a), IntervalSet::from(10..20));
+        assert_eq!(a.shift(-5), set(&[-5..5, 15..25]));
+    }
+
+    #[test]
+    fn test_map() {
+        // The seed-to-soil map from day 5's example, as (source, dest, len).
+        let rules = [(98, 50, 2), (50, 52, 48)];
+        let seeds = set(&[79..93, 55..68]);
+        assert_eq!(seeds.map(rules), set(&[57..70, 81..95]));
+        assert_eq!(
+            IntervalSet::from(97..101).map(rules),
+            set(&[50..52, 99..101])
+        );
+    }
+
+    /// Up to four small ranges, which may overlap or be empty.
+    fn ranges() -> impl Strategy<Value = Vec<Range<i64>>> {
+        prop::collection::vec((-20i64..20, 0i64..10).prop_map(|(s, l)| s..s + l), 0..4)
+    }
+
+    fn brute(ranges: &[Range<i64>]) -> BTreeSet<i64> {
+        ranges.iter().flat_map(Clone::clone).collect()
+    }
+
+    fn values(set: &IntervalSet) -> BTreeSet<i64> {
+        brute(set.ranges())
+    }
+
+    /// Whether the ranges are sorted, non-empty and neither overlap nor touch.
+    fn is_normalized(set: &IntervalSet) -> bool {
+        set.ranges().iter().all(|r| r.start < r.end)
+            && set.ranges().windows(2).all(|w| w[0].end < w[1].start)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_brute_force(a in ranges(), b in ranges(), offset in -10i64..10) {
+            let (sa, sb) = (set(&a), set(&b));
+            let (ba, bb) = (brute(&a), brute(&b));
+
+            prop_assert_eq!(values(&sa), ba.clone());
+            prop_assert_eq!(sa.len(), ba.len() as i64);
+            for x in -30..40 {
+                prop_assert_eq!(sa.contains(x), ba.contains(&x));
+            }
+
+            let results = [
+                (sa.union(&sb), ba.union(&bb).copied().collect::<BTreeSet<_>>()),
+                (sa.intersection(&sb), ba.intersection(&bb).copied().collect()),
+                (sa.difference(&sb), ba.difference(&bb).copied().collect()),
+                (sa.shift(offset), ba.iter().map(|x| x + offset).collect()),
+            ];
+            for (actual, expected) in results {
+                prop_assert!(is_normalized(&actual), "{:?}", actual);
+                prop_assert_eq!(values(&actual), expected);
+            }
+        }
+
+        #[test]
+        fn prop_map_matches_brute_force(
+            a in ranges(),
+            rules in prop::collection::vec((-20i64..20, -20i64..20, 0i64..10), 0..4),
+        ) {
+            let expected: BTreeSet<i64> = brute(&a)
+                .into_iter()
+                .map(|x| {
+                    rules
+                        .iter()
+                        .find(|&&(source, _, len)| (source..source + len).contains(&x))
+                        .map_or(x, |&(source, dest, _)| x - source + dest)
+                })
+                .collect();
+            let actual = set(&a).map(rules.iter().copied());
+            prop_assert!(is_normalized(&actual), "{:?}", actual);
+            prop_assert_eq!(values(&actual), expected);
+        }
+    }
+}
@@ -1,3 +1,31 @@
-pub fn add(left: u32, right: u32) -> u32 {
-    left + right
-}
+//! Shared building blocks for the Advent of Code 2023 solutions.
+
+pub mod answer;
+pub mod bench;
+pub mod cycle;
+pub mod examples;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod intervals;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod ocr;
+pub mod parse;
+pub mod runtime;
+pub mod search;
+pub mod solution;
+
+pub use answer::Answer;
+pub use bench::{BenchOptions, BenchResult};
+pub use cycle::Cycle;
+pub use examples::{Example, ExampleStore};
+pub use geometry::{Direction, Point, Polygon, Vec2, Vec3};
+pub use graph::Graph;
+pub use grid::Grid;
+pub use input::{InputStore, PuzzleInput};
+pub use intervals::IntervalSet;
+pub use parse::{ParseError, Parser};
+pub use solution::{Day, DynSolution, Part, Registry, RunOutput, Solution};
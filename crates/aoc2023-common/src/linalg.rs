@@ -0,0 +1,453 @@
+//! Exact linear algebra over rationals.
+//!
+//! Puzzle coordinates can be in the hundreds of trillions, where an `f64` can't
+//! even represent every integer. Systems are solved exactly by fraction-free
+//! elimination over big integers, so only the solution itself needs to fit in
+//! a [`Rational`] backed by `i128`. Every operation is checked, and fails with
+//! [`LinalgError::Overflow`] rather than giving a wrong answer.
+
+use std::{cmp::Ordering, fmt};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{ToPrimitive, Zero};
+
+/// Why a linear system couldn't be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinalgError {
+    /// The matrix isn't square, or the right-hand side is the wrong length.
+    Dimensions {
+        rows: usize,
+        columns: usize,
+        rhs: usize,
+    },
+    /// The system doesn't have exactly one solution.
+    Singular,
+    /// A value in the solution didn't fit in an `i128`.
+    Overflow,
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dimensions { rows, columns, rhs } => write!(
+                f,
+                "Expected a square matrix and a matching right-hand side, got {rows}x{columns} and {rhs}"
+            ),
+            Self::Singular => write!(f, "The system is singular, so it has no unique solution"),
+            Self::Overflow => write!(f, "The solution doesn't fit in an i128"),
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
+
+/// An exact fraction, always stored in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+/// The gcd of the magnitudes, which can be 2^127 and so doesn't always fit in
+/// an `i128`.
+fn gcd(a: i128, b: i128) -> u128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    pub const fn integer(n: i128) -> Self {
+        Self { numer: n, denom: 1 }
+    }
+
+    /// The fraction `numer / denom`, or `None` if `denom` is zero or the
+    /// fraction in lowest terms doesn't fit.
+    pub fn new(numer: i128, denom: i128) -> Option<Self> {
+        if denom == 0 {
+            return None;
+        }
+        // Work with magnitudes, so that negating i128::MIN can't overflow.
+        let g = gcd(numer, denom);
+        let (n, d) = (numer.unsigned_abs() / g, denom.unsigned_abs() / g);
+        let numer = if (numer < 0) != (denom < 0) {
+            0i128.checked_sub_unsigned(n)?
+        } else {
+            n.try_into().ok()?
+        };
+        Some(Self {
+            numer,
+            denom: d.try_into().ok()?,
+        })
+    }
+
+    pub fn numer(self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numer == 0
+    }
+
+    /// The value as an integer, if it is one.
+    pub fn to_integer(self) -> Option<i128> {
+        (self.denom == 1).then_some(self.numer)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        // Denominators are positive, so their gcd fits in an i128.
+        let g = gcd(self.denom, rhs.denom) as i128;
+        let numer = self
+            .numer
+            .checked_mul(rhs.denom / g)?
+            .checked_add(rhs.numer.checked_mul(self.denom / g)?)?;
+        Self::new(numer, (self.denom / g).checked_mul(rhs.denom)?)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(rhs.checked_neg()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numer: self.numer.checked_neg()?,
+            denom: self.denom,
+        })
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        // Cancel common factors first, to keep the products small. Each gcd is
+        // at most a positive denominator, so fits in an i128.
+        let g1 = gcd(self.numer, rhs.denom) as i128;
+        let g2 = gcd(rhs.numer, self.denom) as i128;
+        Self::new(
+            (self.numer / g1).checked_mul(rhs.numer / g2)?,
+            (self.denom / g2).checked_mul(rhs.denom / g1)?,
+        )
+    }
+
+    /// Divide by `rhs`, or `None` if it's zero or the result overflows.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(Self::new(rhs.denom, rhs.numer)?)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self::integer(n)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self::integer(n.into())
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross-multiplying keeps the order.
+        let lhs = self.numer.checked_mul(other.denom);
+        let rhs = other.numer.checked_mul(self.denom);
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+            _ => (BigInt::from(self.numer) * other.denom)
+                .cmp(&(BigInt::from(other.numer) * self.denom)),
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    /// Formats as `3/2`, or just `3` for integers.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_integer() {
+            Some(n) => write!(f, "{n}"),
+            None => write!(f, "{}/{}", self.numer, self.denom),
+        }
+    }
+}
+
+/// Solve `matrix * x = rhs` for `x`.
+///
+/// The matrix must be square, and the system must have exactly one solution.
+/// This is Gauss-Jordan elimination kept fraction-free with Bareiss's trick:
+/// every division is exact, and the entries never grow beyond a determinant of
+/// the input, so big integers hold them without any rounding.
+pub fn solve<T: Copy + Into<Rational>>(
+    matrix: &[Vec<T>],
+    rhs: &[T],
+) -> Result<Vec<Rational>, LinalgError> {
+    let n = matrix.len();
+    if matrix.iter().any(|row| row.len() != n) || rhs.len() != n {
+        return Err(LinalgError::Dimensions {
+            rows: n,
+            columns: matrix.iter().map(Vec::len).max().unwrap_or(0),
+            rhs: rhs.len(),
+        });
+    }
+
+    // The augmented matrix [matrix | rhs], with each row scaled by the lcm of
+    // its denominators so that every entry is an integer.
+    let mut rows: Vec<Vec<BigInt>> = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, &b)| {
+            let row: Vec<Rational> = row.iter().chain([&b]).map(|&x| x.into()).collect();
+            let lcm = row
+                .iter()
+                .fold(BigInt::from(1), |lcm, x| lcm.lcm(&BigInt::from(x.denom)));
+            row.iter().map(|x| &lcm / x.denom * x.numer).collect()
+        })
+        .collect();
+
+    let mut previous_pivot = BigInt::from(1);
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|&r| !rows[r][col].is_zero())
+            .ok_or(LinalgError::Singular)?;
+        rows.swap(col, pivot);
+
+        let pivot_row = rows[col].clone();
+        for (_, row) in rows.iter_mut().enumerate().filter(|&(r, _)| r != col) {
+            let factor = std::mem::take(&mut row[col]);
+            for (j, x) in row.iter_mut().enumerate().filter(|&(j, _)| j != col) {
+                *x = (&pivot_row[col] * &*x - &factor * &pivot_row[j]) / &previous_pivot;
+            }
+        }
+        previous_pivot = pivot_row[col].clone();
+    }
+
+    // Every pivot is now the determinant, so each unknown is one division.
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let (mut numer, mut denom) = (row[n].clone(), row[i].clone());
+            if denom < BigInt::zero() {
+                (numer, denom) = (-numer, -denom);
+            }
+            let g = numer.gcd(&denom);
+            let (numer, denom) = ((numer / &g).to_i128(), (denom / &g).to_i128());
+            numer
+                .zip(denom)
+                .and_then(|(numer, denom)| Rational::new(numer, denom))
+                .ok_or(LinalgError::Overflow)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::Vec3;
+
+    fn r(numer: i128, denom: i128) -> Rational {
+        Rational::new(numer, denom).unwrap()
+    }
+
+    #[test]
+    fn test_rational() {
+        assert_eq!(r(6, -4), r(-3, 2));
+        assert_eq!(r(-3, 2).to_string(), "-3/2");
+        assert_eq!(r(4, 2).to_string(), "2");
+        assert_eq!(Rational::new(1, 0), None);
+
+        assert_eq!(r(1, 2).checked_add(r(1, 3)), Some(r(5, 6)));
+        assert_eq!(r(1, 2).checked_sub(r(1, 3)), Some(r(1, 6)));
+        assert_eq!(r(2, 3).checked_mul(r(9, 4)), Some(r(3, 2)));
+        assert_eq!(r(2, 3).checked_div(r(4, 3)), Some(r(1, 2)));
+        assert_eq!(r(2, 3).checked_div(Rational::ZERO), None);
+        assert_eq!(
+            Rational::integer(i128::MAX).checked_add(Rational::ONE),
+            None
+        );
+
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < Rational::ZERO);
+    }
+
+    #[test]
+    fn test_rational_extremes() {
+        // -i128::MIN doesn't fit, but reducing it mustn't panic either.
+        assert_eq!(Rational::new(i128::MIN, -1), None);
+        assert_eq!(
+            Rational::new(i128::MIN, 2).map(|x| x.to_string()),
+            Some((i128::MIN / 2).to_string())
+        );
+        assert_eq!(Rational::new(i128::MIN, i128::MIN), Some(Rational::ONE));
+
+        // Cross-multiplying these overflows, but they still compare exactly,
+        // where an f64 would say they're equal.
+        let a = r(i128::MAX, 3);
+        let b = r(i128::MAX - 3, 3);
+        assert_eq!(a.cmp(&b), Ordering::Greater);
+        assert_eq!(b.cmp(&a), Ordering::Less);
+        assert_eq!(a.cmp(&a), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_solve() {
+        // x + 2y = 4, 3x - y = 5
+        let solution = solve(&[vec![1i64, 2], vec![3, -1]], &[4, 5]).unwrap();
+        assert_eq!(solution, vec![r(2, 1), r(1, 1)]);
+
+        // The first pivot is zero, so the rows need swapping, and the
+        // solution isn't an integer.
+        let solution = solve(&[vec![0i64, 2], vec![4, 1]], &[1, 1]).unwrap();
+        assert_eq!(solution, vec![r(1, 8), r(1, 2)]);
+    }
+
+    #[test]
+    fn test_solve_huge_intermediates() {
+        // Eliminating goes through values around huge², but the solution is
+        // small.
+        let huge = i128::MAX / 2;
+        let solution = solve(&[vec![1, huge], vec![huge, 1]], &[huge, 1]).unwrap();
+        assert_eq!(solution, vec![Rational::ZERO, Rational::ONE]);
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        assert_eq!(
+            solve(&[vec![1i64, 2], vec![2, 4]], &[3, 6]),
+            Err(LinalgError::Singular)
+        );
+        assert_eq!(
+            solve(&[vec![1i64, 2]], &[3]),
+            Err(LinalgError::Dimensions {
+                rows: 1,
+                columns: 2,
+                rhs: 1
+            })
+        );
+        assert_eq!(
+            solve(&[vec![r(1, i128::MAX)]], &[Rational::integer(i128::MAX)]),
+            Err(LinalgError::Overflow)
+        );
+        assert_eq!(
+            LinalgError::Singular.to_string(),
+            "The system is singular, so it has no unique solution"
+        );
+    }
+
+    /// The hailstones from day 24's example, as (position, velocity).
+    const HAILSTONES: [(Vec3, Vec3); 5] = [
+        (Vec3::new(19, 13, 30), Vec3::new(-2, 1, -2)),
+        (Vec3::new(18, 19, 22), Vec3::new(-1, -1, -2)),
+        (Vec3::new(20, 25, 34), Vec3::new(-2, -2, -4)),
+        (Vec3::new(12, 31, 28), Vec3::new(-1, -2, -1)),
+        (Vec3::new(20, 19, 15), Vec3::new(1, -5, -3)),
+    ];
+
+    /// The rock at `P` with velocity `V` hits every hailstone, so
+    /// `(P - p) × (V - v) = 0` for each one. Subtracting that equation for two
+    /// hailstones cancels the non-linear `P × V` term, leaving three linear
+    /// equations in `P` and `V`:
+    ///
+    /// `P × (vj - vi) + (pj - pi) × V = pj × vj - pi × vi`
+    fn rock_equations((pi, vi): (Vec3, Vec3), (pj, vj): (Vec3, Vec3)) -> ([Vec<i64>; 3], [i64; 3]) {
+        let w = vj - vi;
+        let u = pj - pi;
+        let c = pj.cross(vj) - pi.cross(vi);
+        (
+            [
+                vec![0, w.z, -w.y, 0, -u.z, u.y],
+                vec![-w.z, 0, w.x, u.z, 0, -u.x],
+                vec![w.y, -w.x, 0, -u.y, u.x, 0],
+            ],
+            [c.x, c.y, c.z],
+        )
+    }
+
+    #[test]
+    fn test_day24_example() {
+        let (m1, b1) = rock_equations(HAILSTONES[0], HAILSTONES[1]);
+        let (m2, b2) = rock_equations(HAILSTONES[0], HAILSTONES[2]);
+        let matrix: Vec<Vec<i64>> = m1.into_iter().chain(m2).collect();
+        let rhs: Vec<i64> = b1.into_iter().chain(b2).collect();
+
+        let solution: Vec<i128> = solve(&matrix, &rhs)
+            .unwrap()
+            .into_iter()
+            .map(|x| x.to_integer().unwrap())
+            .collect();
+        assert_eq!(solution, vec![24, 13, 10, -3, 1, 2]);
+        assert_eq!(solution[..3].iter().sum::<i128>(), 47);
+    }
+
+    #[test]
+    fn test_day24_real_magnitudes() {
+        // Real inputs have positions in the hundreds of trillions, and
+        // velocities in the hundreds.
+        let mut rng = ChaCha8Rng::seed_from_u64(24);
+        let positions = 100_000_000_000_000..400_000_000_000_000;
+        let velocities = -300..300;
+        for _ in 0..50 {
+            let position = Vec3::new(
+                rng.gen_range(positions.clone()),
+                rng.gen_range(positions.clone()),
+                rng.gen_range(positions.clone()),
+            );
+            let velocity = Vec3::new(
+                rng.gen_range(velocities.clone()),
+                rng.gen_range(velocities.clone()),
+                rng.gen_range(velocities.clone()),
+            );
+            let hailstones: Vec<(Vec3, Vec3)> = (0..3)
+                .map(|_| {
+                    let v = Vec3::new(
+                        rng.gen_range(velocities.clone()),
+                        rng.gen_range(velocities.clone()),
+                        rng.gen_range(velocities.clone()),
+                    );
+                    // The rock hits it at some time t.
+                    let t = rng.gen_range(100_000_000_000..1_000_000_000_000);
+                    (position + (velocity - v) * t, v)
+                })
+                .collect();
+
+            let (m1, b1) = rock_equations(hailstones[0], hailstones[1]);
+            let (m2, b2) = rock_equations(hailstones[0], hailstones[2]);
+            let matrix: Vec<Vec<i64>> = m1.into_iter().chain(m2).collect();
+            let rhs: Vec<i64> = b1.into_iter().chain(b2).collect();
+
+            let solution: Vec<i128> = solve(&matrix, &rhs)
+                .unwrap()
+                .into_iter()
+                .map(|x| x.to_integer().unwrap())
+                .collect();
+            let expected = [position, velocity].map(|v| [v.x, v.y, v.z].map(i128::from));
+            assert_eq!(solution, expected.concat());
+        }
+    }
+
+    #[test]
+    fn test_day24_repeated_pair_is_singular() {
+        // The same pair of hailstones twice only gives three independent
+        // equations, which can't pin down six unknowns.
+        let (m1, b1) = rock_equations(HAILSTONES[1], HAILSTONES[2]);
+        let (m2, b2) = rock_equations(HAILSTONES[1], HAILSTONES[2]);
+        let matrix: Vec<Vec<i64>> = m1.into_iter().chain(m2).collect();
+        let rhs: Vec<i64> = b1.into_iter().chain(b2).collect();
+        assert_eq!(solve(&matrix, &rhs), Err(LinalgError::Singular));
+    }
+}
@@ -0,0 +1,200 @@
+//! Number theory for puzzles that reduce to cycle lengths.
+//!
+//! Anything that can overflow returns an error instead of wrapping, because a
+//! wrapped answer looks just as plausible as a right one.
+
+use eyre::{eyre, Result};
+
+/// The greatest common divisor. `gcd(0, 0)` is 0.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, or an error if it doesn't fit in a `u64`.
+pub fn lcm(a: u64, b: u64) -> Result<u64> {
+    if a == 0 || b == 0 {
+        return Ok(0);
+    }
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .ok_or_else(|| eyre!("lcm({a}, {b}) overflows a u64"))
+}
+
+/// The greatest common divisor of every number, or 0 if there are none.
+pub fn gcd_all(numbers: impl IntoIterator<Item = u64>) -> u64 {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// The least common multiple of every number, or 1 if there are none.
+pub fn lcm_all(numbers: impl IntoIterator<Item = u64>) -> Result<u64> {
+    numbers.into_iter().try_fold(1, lcm)
+}
+
+/// The extended Euclidean algorithm: returns `(g, x, y)` such that
+/// `a * x + b * y == g`, where `g` is the non-negative gcd of `a` and `b`.
+///
+/// Fails if `g`, `x` or `y` doesn't fit in an `i64`, which can only happen
+/// when `a` or `b` is `i64::MIN`.
+pub fn extended_gcd(a: i64, b: i64) -> Result<(i64, i64, i64)> {
+    // Every intermediate value fits in an i128, as none is bigger than |a| or |b|.
+    let (mut old_r, mut r) = (i128::from(a), i128::from(b));
+    let (mut old_x, mut x) = (1i128, 0i128);
+    let (mut old_y, mut y) = (0i128, 1i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (old_r, old_x, old_y) = (-old_r, -old_x, -old_y);
+    }
+    match (old_r.try_into(), old_x.try_into(), old_y.try_into()) {
+        (Ok(g), Ok(x), Ok(y)) => Ok((g, x, y)),
+        _ => Err(eyre!("extended_gcd({a}, {b}) overflows an i64")),
+    }
+}
+
+/// `base` to the power `exp`, modulo `modulus`. The result is in `0..modulus`.
+///
+/// Panics if `modulus` isn't positive.
+pub fn mod_pow(base: i64, mut exp: u64, modulus: i64) -> i64 {
+    assert!(modulus > 0, "modulus must be positive, got {modulus}");
+    let m = i128::from(modulus);
+    let mut base = i128::from(base).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// The `x` in `0..modulus` such that `a * x ≡ 1 (mod modulus)`, if `a` and
+/// `modulus` are coprime.
+///
+/// Panics if `modulus` isn't positive.
+pub fn mod_inv(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "modulus must be positive, got {modulus}");
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus)
+        .expect("the gcd of two non-negative i64s fits in an i64");
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Solve a system of congruences `x ≡ residue (mod modulus)` with the Chinese
+/// remainder theorem. The moduli don't need to be coprime.
+///
+/// Returns the smallest non-negative solution and the modulus it repeats
+/// with (the lcm of the moduli), or `None` if the congruences contradict each
+/// other. Fails if the lcm overflows an `i64`.
+///
+/// Panics if any modulus isn't positive.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Result<Option<(i64, i64)>> {
+    let (mut x, mut m) = (0i64, 1i64);
+    for (residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive, got {modulus}");
+        let residue = residue.rem_euclid(modulus);
+
+        // Find k such that x + m * k ≡ residue (mod modulus).
+        let (g, inv, _) = extended_gcd(m, modulus)?;
+        let diff = residue - x;
+        if diff % g != 0 {
+            return Ok(None);
+        }
+        let step = modulus / g;
+        let lcm = m
+            .checked_mul(step)
+            .ok_or_else(|| eyre!("The lcm of the moduli overflows an i64 at modulus {modulus}"))?;
+        let k = (i128::from(diff / g) * i128::from(inv)).rem_euclid(i128::from(step));
+        x = ((i128::from(x) + i128::from(m) * k).rem_euclid(i128::from(lcm))) as i64;
+        m = lcm;
+    }
+    Ok(Some((x, m)))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd_all([12, 18, 8]), 2);
+        assert_eq!(gcd_all([]), 0);
+        assert_eq!(lcm(4, 6).unwrap(), 12);
+        assert_eq!(lcm_all([2, 3, 4]).unwrap(), 12);
+        assert_eq!(lcm_all([]).unwrap(), 1);
+
+        let err = lcm_all([u64::MAX - 1, 3]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "lcm(18446744073709551614, 3) overflows a u64"
+        );
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (7, 0), (0, -7), (17, 5)] {
+            let (g, x, y) = extended_gcd(a, b).unwrap();
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i64);
+            assert_eq!(a * x + b * y, g);
+        }
+
+        // The gcd is 2^63, which is one too big for an i64.
+        assert!(extended_gcd(i64::MIN, 0).is_err());
+        assert!(extended_gcd(0, i64::MIN).is_err());
+        assert_eq!(extended_gcd(i64::MIN, -1).unwrap(), (1, 0, -1));
+        let (g, x, y) = extended_gcd(i64::MIN, i64::MAX).unwrap();
+        assert_eq!(g, 1);
+        assert_eq!(
+            i128::from(i64::MIN) * i128::from(x) + i128::from(i64::MAX) * i128::from(y),
+            1
+        );
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-3, 3, 7), 1);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        // Large enough that the product would overflow an i64.
+        assert_eq!(mod_pow(i64::MAX - 1, 2, i64::MAX), 1);
+
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(4, 8), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]).unwrap(), Some((23, 105)));
+        // Not coprime, but consistent.
+        assert_eq!(crt([(3, 4), (5, 6)]).unwrap(), Some((11, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]).unwrap(), None);
+        assert_eq!(crt([]).unwrap(), Some((0, 1)));
+        assert!(crt([(0, i64::MAX), (0, i64::MAX - 1)]).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_crt_matches_brute_force(
+            congruences in prop::collection::vec((-50i64..50, 1i64..20), 0..4),
+        ) {
+            let modulus = lcm_all(congruences.iter().map(|&(_, m)| m as u64)).unwrap() as i64;
+            let expected = (0..modulus)
+                .find(|x| congruences.iter().all(|&(r, m)| (x - r) % m == 0))
+                .map(|x| (x, modulus));
+            prop_assert_eq!(crt(congruences).unwrap(), expected);
+        }
+    }
+}
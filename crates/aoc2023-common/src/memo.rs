@@ -0,0 +1,262 @@
+//! A cache for memoised recursion, which keeps count of how well it's doing.
+//!
+//! The recursive function takes the [`Memo`] as an argument and wraps its body
+//! in [`Memo::get`], passing the memo on to its recursive calls:
+//!
+//! ```
+//! use aoc2023_common::memo::Memo;
+//!
+//! fn fibonacci(memo: &mut Memo<u32, u64>, n: u32) -> u64 {
+//!     memo.get(n, |memo| match n {
+//!         0 | 1 => n.into(),
+//!         _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+//!     })
+//! }
+//!
+//! assert_eq!(fibonacci(&mut Memo::new("fibonacci"), 90), 2880067194370816120);
+//! ```
+//!
+//! When a memo is dropped, its [`MemoStats`] are logged and recorded with the
+//! [`runtime`], so the TUI can show them. Stats from memos with the same name
+//! are added together, as solutions often make a fresh memo per input line.
+
+use std::{fmt, hash::Hash};
+
+use rustc_hash::FxHashMap;
+
+use crate::runtime;
+
+/// How effective a memo's cache was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoStats {
+    pub name: &'static str,
+    /// Lookups that found a cached value.
+    pub hits: u64,
+    /// Lookups that had to compute the value.
+    pub misses: u64,
+    /// Values in the cache when it was dropped.
+    pub entries: u64,
+}
+
+impl MemoStats {
+    /// The fraction of lookups that found a cached value, or `None` if there
+    /// weren't any lookups.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+
+    /// Add the stats of another memo with the same name to these.
+    pub fn merge(&mut self, other: &Self) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.entries += other.entries;
+    }
+}
+
+impl fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} hits, {} misses, {} entries",
+            self.name, self.hits, self.misses, self.entries
+        )?;
+        if let Some(rate) = self.hit_rate() {
+            write!(f, " ({:.1}% hit rate)", rate * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// A cache of computed values, keyed by the arguments that vary between calls.
+pub struct Memo<K, V> {
+    name: &'static str,
+    cache: FxHashMap<K, V>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    /// Create an empty memo. The name is only used to report its stats.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            cache: FxHashMap::default(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// The cached value for `key`, or else the value computed by `f`, which is
+    /// cached for next time. `f` gets the memo back so that it can recurse.
+    pub fn get(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = f(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// The number of cached values.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Forget every cached value, but keep counting.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+impl<K, V> Memo<K, V> {
+    pub fn stats(&self) -> MemoStats {
+        MemoStats {
+            name: self.name,
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len() as u64,
+        }
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        let stats = self.stats();
+        if stats.hit_rate().is_some() {
+            tracing::debug!(
+                name = stats.name,
+                hits = stats.hits,
+                misses = stats.misses,
+                entries = stats.entries,
+                "Memo cache stats"
+            );
+            runtime::record_memo(stats);
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for Memo<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Memo")
+            .field("name", &self.name)
+            .field("len", &self.cache.len())
+            .field("hits", &self.hits)
+            .field("misses", &self.misses)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::runtime::RunContext;
+
+    /// Day 12's spring arrangements: the number of ways to fill in the `?`s so
+    /// that the runs of `#` match `groups`.
+    fn arrangements(memo: &mut Memo<(usize, usize), u64>, springs: &[u8], groups: &[usize]) -> u64 {
+        memo.get((springs.len(), groups.len()), |memo| {
+            let Some((&first, rest)) = springs.split_first() else {
+                return u64::from(groups.is_empty());
+            };
+            let mut count = 0;
+            if first != b'#' {
+                count += arrangements(memo, rest, groups);
+            }
+            if let Some((&group, other_groups)) = groups.split_first() {
+                let fits = springs.len() >= group
+                    && !springs[..group].contains(&b'.')
+                    && springs.get(group) != Some(&b'#');
+                if first != b'.' && fits {
+                    let after = springs.get(group + 1..).unwrap_or_default();
+                    count += arrangements(memo, after, other_groups);
+                }
+            }
+            count
+        })
+    }
+
+    fn count(row: &str) -> (u64, MemoStats) {
+        let (springs, groups) = row.split_once(' ').unwrap();
+        let groups: Vec<usize> = groups.split(',').map(|g| g.parse().unwrap()).collect();
+        let mut memo = Memo::new("arrangements");
+        let count = arrangements(&mut memo, springs.as_bytes(), &groups);
+        (count, memo.stats())
+    }
+
+    #[test]
+    fn test_arrangements() {
+        assert_eq!(count("???.### 1,1,3").0, 1);
+        assert_eq!(count("?###???????? 3,2,1").0, 10);
+
+        let unfolded = "????.######..#####.?????.######..#####.?????.######..#####.?????.######..#####.?????.######..#####. 1,6,5,1,6,5,1,6,5,1,6,5,1,6,5";
+        let (count, stats) = count(unfolded);
+        assert_eq!(count, 2500);
+        assert!(stats.hits > 0);
+        assert!(stats.hit_rate().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut memo = Memo::new("square");
+        assert_eq!(memo.get(3, |_| 9), 9);
+        assert_eq!(memo.get(3, |_| unreachable!()), 9);
+        assert_eq!(memo.get(4, |_| 16), 16);
+        assert_eq!(memo.len(), 2);
+
+        let stats = memo.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+        assert_eq!(
+            stats.to_string(),
+            "square: 1 hits, 2 misses, 2 entries (33.3% hit rate)"
+        );
+    }
+
+    #[test]
+    fn test_recorded_on_drop() {
+        let context = RunContext::new();
+        context.enter(|| {
+            let mut used = Memo::new("used");
+            used.get(1, |_| 1);
+            // Memos that were never used aren't worth reporting.
+            let _unused: Memo<u8, u8> = Memo::new("unused");
+        });
+        let memos = context.notes().memos;
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].name, "used");
+    }
+
+    #[test]
+    fn test_merged_by_name() {
+        let context = RunContext::new();
+        let rows = [
+            "???.### 1,1,3",
+            "?###???????? 3,2,1",
+            "?#?#?#?#?#?#?#? 1,3,1,6",
+        ];
+        let expected = context.enter(|| {
+            rows.iter().fold(
+                MemoStats {
+                    name: "arrangements",
+                    hits: 0,
+                    misses: 0,
+                    entries: 0,
+                },
+                |mut total, row| {
+                    total.merge(&count(row).1);
+                    total
+                },
+            )
+        });
+        // One line for every row's memo, rather than one line per row.
+        assert_eq!(context.notes().memos, vec![expected]);
+    }
+}
@@ -0,0 +1,250 @@
+//! Reading capital letters drawn in `#` pixels.
+//!
+//! A few puzzles draw their answer rather than computing it, in one of two
+//! fonts: letters 6 pixels tall and about 4 wide, or 10 tall and 6 wide. The
+//! font is picked by the height of the drawing, and letters are split apart at
+//! blank columns, so it doesn't matter how much padding surrounds them.
+
+use std::fmt;
+
+use crate::Grid;
+
+/// The letters 6 pixels tall, as drawn with no blank columns either side.
+const SMALL: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// The letters 10 pixels tall.
+const LARGE: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Why a drawing couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Nothing was drawn.
+    Empty,
+    /// The letters aren't the height of either font.
+    Height(usize),
+    /// The letter at `index`, counting from 0, isn't in the font.
+    UnknownGlyph { index: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "There are no letters to read"),
+            Self::Height(height) => write!(
+                f,
+                "Letters are 6 or 10 pixels tall, but these are {height} pixels tall"
+            ),
+            Self::UnknownGlyph { index, glyph } => {
+                write!(f, "Letter {} isn't in either font:\n{glyph}", index + 1)
+            },
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Read the letters drawn with `#` in `art`. Every other character is blank.
+pub fn read(art: &str) -> Result<String, OcrError> {
+    let lines: Vec<&[u8]> = art.lines().map(str::as_bytes).collect();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let grid = Grid::from_fn(width, lines.len(), |p| {
+        lines[p.y as usize].get(p.x as usize) == Some(&b'#')
+    });
+    read_grid(&grid)
+}
+
+/// Read the letters drawn by the `true` cells of `grid`.
+pub fn read_grid(grid: &Grid<bool>) -> Result<String, OcrError> {
+    let lit_rows: Vec<usize> = (0..grid.height())
+        .filter(|&y| grid.row(y).contains(&true))
+        .collect();
+    let (Some(&top), Some(&bottom)) = (lit_rows.first(), lit_rows.last()) else {
+        return Err(OcrError::Empty);
+    };
+    let font = match bottom - top + 1 {
+        6 => SMALL,
+        10 => LARGE,
+        height => return Err(OcrError::Height(height)),
+    };
+
+    // Runs of columns with something drawn in them, each of which is a letter.
+    let mut glyphs: Vec<Vec<usize>> = Vec::new();
+    let mut previous_lit = false;
+    for x in 0..grid.width() {
+        let lit = grid.column(x).any(|&on| on);
+        match glyphs.last_mut() {
+            Some(glyph) if lit && previous_lit => glyph.push(x),
+            _ if lit => glyphs.push(vec![x]),
+            _ => {},
+        }
+        previous_lit = lit;
+    }
+
+    glyphs
+        .iter()
+        .enumerate()
+        .map(|(index, columns)| {
+            let glyph = (top..=bottom)
+                .map(|y| {
+                    let row = grid.row(y);
+                    columns
+                        .iter()
+                        .map(|&x| if row[x] { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            font.iter()
+                .find(|&&(_, pattern)| pattern == glyph)
+                .map(|&(letter, _)| letter)
+                .ok_or(OcrError::UnknownGlyph { index, glyph })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_small() {
+        let art = "\
+.##..###...##..####.####..##..#..#.###...##.#..#.#.....##..###..###...###.#..#.#...#.####
+#..#.#..#.#..#.#....#....#..#.#..#..#.....#.#.#..#....#..#.#..#.#..#.#....#..#.#...#....#
+#..#.###..#....###..###..#....####..#.....#.##...#....#..#.#..#.#..#.#....#..#..#.#....#.
+####.#..#.#....#....#....#.##.#..#..#.....#.#.#..#....#..#.###..###...##..#..#...#....#..
+#..#.#..#.#..#.#....#....#..#.#..#..#..#..#.#.#..#....#..#.#....#.#.....#.#..#...#...#...
+#..#.###...##..####.#.....###.#..#.###..##..#..#.####..##..#....#..#.###...##....#...####
+";
+        assert_eq!(read(art), Ok("ABCEFGHIJKLOPRSUYZ".to_string()));
+    }
+
+    #[test]
+    fn test_large_with_padding() {
+        let art = "
+  ..............................
+  ..#####...#....#...######.....
+  ..#....#..##...#...#..........
+  ..#....#..##...#...#..........
+  ..#....#..#.#..#...#..........
+  ..#####...#.#..#...#####......
+  ..#..#....#..#.#...#..........
+  ..#...#...#..#.#...#..........
+  ..#...#...#...##...#..........
+  ..#....#..#...##...#..........
+  ..#....#..#....#...######.....
+";
+        assert_eq!(read(art), Ok("RNE".to_string()));
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = Grid::from_fn(4, 6, |p| {
+            ["#..#", "#..#", "####", "#..#", "#..#", "#..#"][p.y as usize].as_bytes()[p.x as usize]
+                == b'#'
+        });
+        assert_eq!(read_grid(&grid), Ok("H".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(read("....\n...."), Err(OcrError::Empty));
+        assert_eq!(read("#\n#\n#"), Err(OcrError::Height(3)));
+
+        let error =
+            read("#..#.####\n#..#.#..#\n####.####\n#..#.#..#\n#..#.####\n#..#.#..#").unwrap_err();
+        assert_eq!(
+            error,
+            OcrError::UnknownGlyph {
+                index: 1,
+                glyph: "####\n#..#\n####\n#..#\n####\n#..#".to_string()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Letter 2 isn't in either font:\n####\n#..#\n####\n#..#\n####\n#..#"
+        );
+    }
+}
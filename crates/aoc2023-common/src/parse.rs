@@ -0,0 +1,440 @@
+//! A small hand-rolled parser for puzzle input.
+//!
+//! A [`Parser`] is a cursor into the input, with a method for each kind of
+//! thing a puzzle is likely to contain. Methods that can fail return a
+//! [`ParseError`] pointing at where they failed, which is displayed with the
+//! offending line and a caret under the problem:
+//!
+//! ```text
+//! Expected an integer at line 3, column 9, but found 'x'
+//!   |
+//! 3 | Game 3: x green
+//!   |         ^
+//! ```
+
+use std::{any, fmt, str::FromStr};
+
+/// Why some input couldn't be parsed, and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line the error is on, counting from 1.
+    pub line: usize,
+    /// The character within the line the error is at, counting from 1.
+    pub column: usize,
+    /// The whole line the error is on, without its newline.
+    pub line_text: String,
+    /// What the parser was looking for, e.g. "an integer".
+    pub expected: String,
+    /// What it found instead, e.g. "'x'" or "the end of the input".
+    pub found: String,
+}
+
+impl ParseError {
+    /// The first line of the error, without the diagram.
+    pub fn message(&self) -> String {
+        format!(
+            "Expected {} at line {}, column {}, but found {}",
+            self.expected, self.line, self.column, self.found
+        )
+    }
+
+    /// The padding that lines the caret up under the error, keeping any tabs
+    /// in the line so it lines up however wide they're shown.
+    pub fn caret_indent(&self) -> String {
+        self.line_text
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{}", self.message())?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        write!(f, "{gutter} | {}^", self.caret_indent())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+/// A cursor into some input, which moves forward as things are parsed.
+#[derive(Debug, Clone)]
+pub struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    /// Parse the whole of `input` with `f`, allowing trailing whitespace.
+    pub fn parse_all<T>(input: &'a str, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let mut parser = Self::new(input);
+        let value = f(&mut parser)?;
+        parser.take_while(char::is_whitespace);
+        parser.end()?;
+        Ok(value)
+    }
+
+    /// The input that hasn't been parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    /// The next character, without consuming it.
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// An error at the current position, saying what was expected there.
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, expected)
+    }
+
+    fn error_at(&self, pos: usize, expected: impl Into<String>) -> ParseError {
+        let line_start = self.input[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.input[pos..]
+            .find('\n')
+            .map_or(self.input.len(), |i| pos + i);
+        let found = match self.input[pos..].chars().next() {
+            None => "the end of the input".to_string(),
+            Some('\n') => "the end of the line".to_string(),
+            Some(c) => format!("{c:?}"),
+        };
+        ParseError {
+            line: self.input[..pos].matches('\n').count() + 1,
+            column: self.input[line_start..pos].chars().count() + 1,
+            line_text: self.input[line_start..line_end].to_string(),
+            expected: expected.into(),
+            found,
+        }
+    }
+
+    /// Expect the end of the input.
+    pub fn end(&self) -> Result<()> {
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            Err(self.error("the end of the input"))
+        }
+    }
+
+    /// Consume `keyword` if the input starts with it.
+    pub fn try_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.rest().starts_with(keyword);
+        if found {
+            self.pos += keyword.len();
+        }
+        found
+    }
+
+    /// Expect the input to continue with exactly `keyword`.
+    pub fn keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.try_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(format!("{keyword:?}")))
+        }
+    }
+
+    /// Consume the next character, whatever it is.
+    pub fn char(&mut self) -> Result<char> {
+        let c = self.peek().ok_or_else(|| self.error("a character"))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    /// Consume the next character, which must be one of `chars`.
+    pub fn one_of(&mut self, chars: &str) -> Result<char> {
+        match self.peek() {
+            Some(c) if chars.contains(c) => self.char(),
+            _ => Err(self.error(format!("one of {chars:?}"))),
+        }
+    }
+
+    /// Consume characters for as long as `f` accepts them.
+    pub fn take_while(&mut self, mut f: impl FnMut(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Skip any spaces and tabs, but not newlines.
+    pub fn spaces(&mut self) {
+        self.take_while(|c| c == ' ' || c == '\t');
+    }
+
+    /// Consume a word made of letters, digits and underscores.
+    pub fn word(&mut self) -> Result<&'a str> {
+        match self.take_while(|c| c.is_alphanumeric() || c == '_') {
+            "" => Err(self.error("a word")),
+            word => Ok(word),
+        }
+    }
+
+    /// Parse an integer without a sign.
+    pub fn unsigned<T: FromStr>(&mut self) -> Result<T> {
+        let start = self.pos;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        self.number(start, digits)
+    }
+
+    /// Parse an integer with an optional `-` or `+` sign.
+    pub fn signed<T: FromStr>(&mut self) -> Result<T> {
+        let start = self.pos;
+        if !self.try_keyword("-") {
+            self.try_keyword("+");
+        }
+        self.take_while(|c| c.is_ascii_digit());
+        let input = self.input;
+        self.number(start, &input[start..self.pos])
+    }
+
+    fn number<T: FromStr>(&mut self, start: usize, text: &str) -> Result<T> {
+        if !text.ends_with(|c: char| c.is_ascii_digit()) {
+            self.pos = start;
+            return Err(self.error("an integer"));
+        }
+        text.parse().map_err(|_| {
+            let ty = any::type_name::<T>();
+            self.error_at(start, format!("an integer that fits in a {ty}"))
+        })
+    }
+
+    /// Run `f`, and undo anything it consumed if it fails.
+    pub fn optional<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Option<T> {
+        let start = self.pos;
+        let value = f(self).ok();
+        if value.is_none() {
+            self.pos = start;
+        }
+        value
+    }
+
+    /// Parse one or more items with `item`, separated by `separator`.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.try_keyword(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Parse one or more items with `item`, separated by spaces on one line.
+    pub fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        self.spaces();
+        let mut items = vec![item(self)?];
+        loop {
+            self.spaces();
+            if matches!(self.peek(), None | Some('\n')) {
+                return Ok(items);
+            }
+            items.push(item(self)?);
+        }
+    }
+
+    /// Parse lines with `item`, each of which must parse the whole line.
+    ///
+    /// Stops at the end of the input or at a blank line, so that sections of
+    /// input separated by blank lines can be parsed one after the other. The
+    /// newline ending the last line is consumed, but the blank line isn't.
+    pub fn lines<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        while !matches!(self.peek(), None | Some('\n')) {
+            items.push(item(self)?);
+            if !self.try_keyword("\n") && !self.is_at_end() {
+                return Err(self.error("the end of the line"));
+            }
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    type Round = Vec<(u32, String)>;
+
+    /// Day 2's games: how many cubes of each colour were shown in each round.
+    fn game(p: &mut Parser) -> Result<(u32, Vec<Round>)> {
+        p.keyword("Game ")?;
+        let id = p.unsigned()?;
+        p.keyword(": ")?;
+        let rounds = p.separated("; ", |p| {
+            p.separated(", ", |p| {
+                let count = p.unsigned()?;
+                p.keyword(" ")?;
+                Ok((count, p.word()?.to_string()))
+            })
+        })?;
+        Ok((id, rounds))
+    }
+
+    #[test]
+    fn test_games() {
+        let input = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green
+";
+        let games = Parser::parse_all(input, |p| p.lines(game)).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].0, 2);
+        assert_eq!(
+            games[0].1[1],
+            vec![
+                (1, "red".to_string()),
+                (2, "green".to_string()),
+                (6, "blue".to_string())
+            ]
+        );
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Rule {
+        Test {
+            category: char,
+            op: char,
+            value: u32,
+            target: String,
+        },
+        Always(String),
+    }
+
+    type Workflow = (String, Vec<Rule>);
+
+    /// Day 19's workflows, followed by a blank line and the parts.
+    fn workflows(p: &mut Parser) -> Result<(Vec<Workflow>, Vec<Vec<u32>>)> {
+        let workflows = p.lines(|p| {
+            let name = p.word()?.to_string();
+            p.keyword("{")?;
+            let rules = p.separated(",", |p| {
+                let test = p.optional(|p| {
+                    let category = p.one_of("xmas")?;
+                    let op = p.one_of("<>")?;
+                    let value = p.unsigned()?;
+                    p.keyword(":")?;
+                    Ok((category, op, value))
+                });
+                let target = p.word()?.to_string();
+                Ok(match test {
+                    Some((category, op, value)) => Rule::Test {
+                        category,
+                        op,
+                        value,
+                        target,
+                    },
+                    None => Rule::Always(target),
+                })
+            })?;
+            p.keyword("}")?;
+            Ok((name, rules))
+        })?;
+        p.keyword("\n")?;
+        let parts = p.lines(|p| {
+            p.keyword("{")?;
+            let ratings = p.separated(",", |p| {
+                p.one_of("xmas")?;
+                p.keyword("=")?;
+                p.unsigned()
+            })?;
+            p.keyword("}")?;
+            Ok(ratings)
+        })?;
+        Ok((workflows, parts))
+    }
+
+    #[test]
+    fn test_workflows() {
+        let input =
+            "px{a<2006:qkq,m>2090:A,rfg}\nin{s<1351:px,qqz}\n\n{x=787,m=2655,a=1222,s=2876}\n";
+        let (workflows, parts) = Parser::parse_all(input, workflows).unwrap();
+        assert_eq!(workflows.len(), 2);
+        assert_eq!(
+            workflows[0].1,
+            vec![
+                Rule::Test {
+                    category: 'a',
+                    op: '<',
+                    value: 2006,
+                    target: "qkq".into()
+                },
+                Rule::Test {
+                    category: 'm',
+                    op: '>',
+                    value: 2090,
+                    target: "A".into()
+                },
+                Rule::Always("rfg".into()),
+            ]
+        );
+        assert_eq!(parts, vec![vec![787, 2655, 1222, 2876]]);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let mut p = Parser::new("12 -3 +4 -");
+        assert_eq!(
+            p.list(Parser::signed::<i64>),
+            Err(p.error_at(9, "an integer"))
+        );
+
+        let mut p = Parser::new("0 3 6 -9  12\n");
+        assert_eq!(p.list(Parser::signed::<i8>).unwrap(), vec![0, 3, 6, -9, 12]);
+        assert_eq!(p.rest(), "\n");
+
+        let mut p = Parser::new("300");
+        let error = p.unsigned::<u8>().unwrap_err();
+        assert_eq!(error.expected, "an integer that fits in a u8");
+        assert_eq!(error.column, 1);
+    }
+
+    #[test]
+    fn test_error_diagram() {
+        let input = "Game 1: 3 blue\nGame 2: x green\n";
+        let error = Parser::parse_all(input, |p| p.lines(game)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 9));
+        assert_eq!(
+            error.to_string(),
+            "\
+Expected an integer at line 2, column 9, but found 'x'
+  |
+2 | Game 2: x green
+  |         ^"
+        );
+
+        let error = Parser::parse_all("Game 1: 3 blue; ", |p| p.lines(game)).unwrap_err();
+        assert_eq!(
+            error.message(),
+            "Expected an integer at line 1, column 17, but found the end of the input"
+        );
+        let error = Parser::parse_all("\tGame 1", |p| p.lines(game)).unwrap_err();
+        assert_eq!(error.caret_indent(), "");
+        let error = Parser::parse_all("x\t\ty", |p| {
+            p.keyword("x\t")?;
+            p.word()
+        })
+        .unwrap_err();
+        assert_eq!(error.caret_indent(), " \t");
+        assert_eq!(error.found, "'\\t'");
+    }
+}
@@ -0,0 +1,230 @@
+//! Progress reporting and cancellation for long-running solutions.
+//!
+//! Runners install a [`RunContext`] on the thread a solution runs on with
+//! [`RunContext::enter`]. Solutions can then call [`progress`] and
+//! [`check_cancelled`] from anywhere, without threading a handle through every
+//! function. Both are cheap no-ops when no context is installed, e.g. in tests.
+//!
+//! The context also collects [`RunNotes`]: anything worth showing alongside
+//! the answer, such as the cycles found by the [`cycle`](crate::cycle) module.
+
+use std::{
+    cell::RefCell,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{cycle::Cycle, memo::MemoStats};
+
+/// Progress is stored as millionths of the total work.
+const PROGRESS_SCALE: f64 = 1_000_000.0;
+/// Marks that no progress has been reported yet.
+const NO_PROGRESS: u32 = u32::MAX;
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Arc<RunContext>>> = const { RefCell::new(None) };
+}
+
+/// Things a solution found out while running, in the order it found them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunNotes {
+    pub cycles: Vec<Cycle>,
+    pub memos: Vec<MemoStats>,
+}
+
+impl RunNotes {
+    pub fn is_empty(&self) -> bool {
+        self.cycles.is_empty() && self.memos.is_empty()
+    }
+}
+
+/// Shared state between a running solution and whatever is running it.
+#[derive(Debug)]
+pub struct RunContext {
+    cancelled: AtomicBool,
+    progress: AtomicU32,
+    notes: Mutex<RunNotes>,
+}
+
+impl Default for RunContext {
+    fn default() -> Self {
+        Self {
+            cancelled: AtomicBool::new(false),
+            progress: AtomicU32::new(NO_PROGRESS),
+            notes: Mutex::default(),
+        }
+    }
+}
+
+impl RunContext {
+    /// Create a new, shareable context.
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Ask the solution to stop as soon as possible.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the solution has been asked to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The latest progress reported by the solution, from 0 to 1.
+    ///
+    /// Returns `None` if the solution hasn't reported any progress.
+    pub fn progress(&self) -> Option<f32> {
+        match self.progress.load(Ordering::Relaxed) {
+            NO_PROGRESS => None,
+            p => Some((p as f64 / PROGRESS_SCALE) as f32),
+        }
+    }
+
+    /// Everything the solution has noted so far.
+    pub fn notes(&self) -> RunNotes {
+        self.notes.lock().unwrap().clone()
+    }
+
+    /// Run `f` with this context installed on the current thread.
+    ///
+    /// Any previously installed context is restored afterwards, even if `f` panics.
+    pub fn enter<T>(self: &Arc<Self>, f: impl FnOnce() -> T) -> T {
+        struct Restore(Option<Arc<RunContext>>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CONTEXT.with(|c| *c.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(CONTEXT.with(|c| c.borrow_mut().replace(self.clone())));
+        f()
+    }
+
+    fn set_progress(&self, fraction: f64) {
+        let scaled = (fraction.clamp(0.0, 1.0) * PROGRESS_SCALE) as u32;
+        self.progress.store(scaled, Ordering::Relaxed);
+    }
+}
+
+/// Run `f` with the current thread's context, if there is one.
+fn with_context<T>(f: impl FnOnce(&RunContext) -> T) -> Option<T> {
+    CONTEXT.with(|c| c.borrow().as_deref().map(f))
+}
+
+/// Report that `done` out of `total` units of work have been completed.
+pub fn progress(done: usize, total: usize) {
+    with_context(|context| {
+        let fraction = if total == 0 {
+            1.0
+        } else {
+            done as f64 / total as f64
+        };
+        context.set_progress(fraction);
+    });
+}
+
+/// Record a cycle found while running, so it can be shown with the answer.
+pub fn record_cycle(cycle: Cycle) {
+    with_context(|context| context.notes.lock().unwrap().cycles.push(cycle));
+}
+
+/// Record how well a memo's cache did, so it can be shown with the answer.
+///
+/// Stats for a memo with the same name as one already recorded are added to
+/// that one's.
+pub fn record_memo(stats: MemoStats) {
+    with_context(|context| {
+        let memos = &mut context.notes.lock().unwrap().memos;
+        match memos.iter_mut().find(|memo| memo.name == stats.name) {
+            Some(memo) => memo.merge(&stats),
+            None => memos.push(stats),
+        }
+    });
+}
+
+/// Remove and return everything noted so far in the current context.
+pub fn take_notes() -> RunNotes {
+    with_context(|context| std::mem::take(&mut *context.notes.lock().unwrap())).unwrap_or_default()
+}
+
+/// Whether a solution is running on the current thread.
+pub fn is_running() -> bool {
+    with_context(|_| ()).is_some()
+}
+
+/// Whether the running solution has been asked to stop.
+pub fn is_cancelled() -> bool {
+    with_context(RunContext::is_cancelled).unwrap_or(false)
+}
+
+/// Returns an error if the running solution has been asked to stop.
+///
+/// Meant to be used with `?` inside long loops.
+pub fn check_cancelled() -> Result<(), Cancelled> {
+    if is_cancelled() {
+        Err(Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+/// The error returned by [`check_cancelled`] when a solution has been cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_no_context_is_noop() {
+        progress(1, 2);
+        assert!(!is_running());
+        assert!(!is_cancelled());
+        assert_eq!(check_cancelled(), Ok(()));
+    }
+
+    #[test]
+    fn test_progress_and_cancellation() {
+        let context = RunContext::new();
+        assert_eq!(context.progress(), None);
+
+        context.enter(|| {
+            assert!(is_running());
+            progress(1, 4);
+            assert_eq!(check_cancelled(), Ok(()));
+        });
+        assert_eq!(context.progress(), Some(0.25));
+
+        context.cancel();
+        context.enter(|| assert_eq!(check_cancelled(), Err(Cancelled)));
+
+        // The context is only installed while inside `enter`.
+        assert!(!is_cancelled());
+    }
+
+    #[test]
+    fn test_context_restored_after_panic() {
+        let context = RunContext::new();
+        context.cancel();
+        let result = std::panic::catch_unwind(|| context.enter(|| panic!("oh no")));
+        assert!(result.is_err());
+        assert!(!is_cancelled());
+    }
+}
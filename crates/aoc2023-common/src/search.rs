@@ -0,0 +1,570 @@
+//! Graph searches over arbitrary state.
+//!
+//! Every search takes a start state and a successor closure, so the "graph" can
+//! be anything: a grid position, or a position plus a direction plus how far
+//! the crucible has gone in a straight line. The searches are iterators that
+//! yield states in the order they're settled, which lets the TUI animate the
+//! [frontier](BestFirst::frontier). The [`bfs`], [`dijkstra`] and [`astar`]
+//! functions wrap them for the common case of finding one goal.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// A path cost. [`Default`] is used as zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+/// A path through the search space, from the start state to the goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    /// Every state along the path, including the start and the goal.
+    pub states: Vec<S>,
+}
+
+/// A state that has been settled, i.e. its cheapest cost is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Visit<S, C> {
+    pub state: S,
+    pub cost: C,
+}
+
+#[derive(Debug)]
+struct Node<S, C> {
+    state: S,
+    cost: C,
+    parent: Option<usize>,
+}
+
+/// Every state reached so far, with the cheapest known way of reaching it.
+#[derive(Debug)]
+struct Tree<S, C> {
+    nodes: Vec<Node<S, C>>,
+    best: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Tree<S, C> {
+    fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            best: HashMap::new(),
+        }
+    }
+
+    /// Record a way of reaching `state`, returning its node if it's the cheapest so far.
+    fn insert(&mut self, state: S, cost: C, parent: Option<usize>) -> Option<usize> {
+        if let Some(&i) = self.best.get(&state) {
+            if self.nodes[i].cost <= cost {
+                return None;
+            }
+        }
+        let i = self.nodes.len();
+        self.nodes.push(Node {
+            state: state.clone(),
+            cost,
+            parent,
+        });
+        self.best.insert(state, i);
+        Some(i)
+    }
+
+    /// Whether node `i` is still the cheapest way of reaching its state.
+    fn is_best(&self, i: usize) -> bool {
+        self.best.get(&self.nodes[i].state) == Some(&i)
+    }
+
+    fn cost(&self, state: &S) -> Option<C> {
+        self.best.get(state).map(|&i| self.nodes[i].cost)
+    }
+
+    fn path(&self, state: &S) -> Option<Path<S, C>> {
+        let mut i = *self.best.get(state)?;
+        let cost = self.nodes[i].cost;
+        let mut states = vec![self.nodes[i].state.clone()];
+        while let Some(parent) = self.nodes[i].parent {
+            states.push(self.nodes[parent].state.clone());
+            i = parent;
+        }
+        states.reverse();
+        Some(Path { cost, states })
+    }
+}
+
+/// A breadth-first search, where every step costs 1.
+pub struct Bfs<S, F> {
+    tree: Tree<S, usize>,
+    queue: VecDeque<usize>,
+    successors: F,
+}
+
+impl<S, F, I> Bfs<S, F>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    pub fn new(start: S, successors: F) -> Self {
+        let mut tree = Tree::new();
+        let root = tree.insert(start, 0, None).unwrap();
+        Self {
+            tree,
+            queue: VecDeque::from([root]),
+            successors,
+        }
+    }
+
+    /// The number of steps to `state`, if it has been reached.
+    pub fn cost(&self, state: &S) -> Option<usize> {
+        self.tree.cost(state)
+    }
+
+    /// The shortest path to `state`, if it has been reached.
+    pub fn path(&self, state: &S) -> Option<Path<S, usize>> {
+        self.tree.path(state)
+    }
+
+    /// The states that have been reached but not yet settled.
+    pub fn frontier(&self) -> impl Iterator<Item = &S> {
+        self.queue.iter().map(|&i| &self.tree.nodes[i].state)
+    }
+}
+
+impl<S, F, I> Iterator for Bfs<S, F>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    type Item = Visit<S, usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.queue.pop_front()?;
+        let Node { state, cost, .. } = &self.tree.nodes[i];
+        let (state, cost) = (state.clone(), *cost);
+
+        for next in (self.successors)(&state) {
+            if !self.tree.best.contains_key(&next) {
+                let j = self.tree.insert(next, cost + 1, Some(i)).unwrap();
+                self.queue.push_back(j);
+            }
+        }
+        Some(Visit { state, cost })
+    }
+}
+
+/// A best-first search with weighted steps: Dijkstra's algorithm, or A* when
+/// given a heuristic.
+pub struct BestFirst<S, C, F, H> {
+    tree: Tree<S, C>,
+    heap: BinaryHeap<Reverse<(C, usize)>>,
+    successors: F,
+    heuristic: H,
+}
+
+/// Dijkstra's algorithm is A* with a heuristic that's always zero.
+pub type Dijkstra<S, C, F> = BestFirst<S, C, F, fn(&S) -> C>;
+
+fn zero<S, C: Default>(_: &S) -> C {
+    C::default()
+}
+
+impl<S, C, F, I> BestFirst<S, C, F, fn(&S) -> C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    /// Dijkstra's algorithm. `successors` returns each neighbouring state along
+    /// with the cost of stepping to it.
+    pub fn dijkstra(start: S, successors: F) -> Self {
+        Self::astar(start, successors, zero::<S, C>)
+    }
+}
+
+impl<S, C, F, H, I> BestFirst<S, C, F, H>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    H: FnMut(&S) -> C,
+    I: IntoIterator<Item = (S, C)>,
+{
+    /// A* search. The heuristic must never overestimate the remaining cost, or
+    /// the paths found may not be the cheapest.
+    pub fn astar(start: S, successors: F, mut heuristic: H) -> Self {
+        let mut tree = Tree::new();
+        let priority = heuristic(&start);
+        let root = tree.insert(start, C::default(), None).unwrap();
+        Self {
+            tree,
+            heap: BinaryHeap::from([Reverse((priority, root))]),
+            successors,
+            heuristic,
+        }
+    }
+
+    /// The cheapest known cost of reaching `state`.
+    ///
+    /// This is only guaranteed to be the cheapest overall once `state` has been visited.
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.tree.cost(state)
+    }
+
+    /// The cheapest known path to `state`.
+    ///
+    /// This is only guaranteed to be the cheapest overall once `state` has been visited.
+    pub fn path(&self, state: &S) -> Option<Path<S, C>> {
+        self.tree.path(state)
+    }
+
+    /// The states that have been reached but not yet settled.
+    pub fn frontier(&self) -> impl Iterator<Item = &S> {
+        self.heap
+            .iter()
+            .filter(|Reverse((_, i))| self.tree.is_best(*i))
+            .map(|Reverse((_, i))| &self.tree.nodes[*i].state)
+    }
+}
+
+impl<S, C, F, H, I> Iterator for BestFirst<S, C, F, H>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    H: FnMut(&S) -> C,
+    I: IntoIterator<Item = (S, C)>,
+{
+    type Item = Visit<S, C>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip nodes that were superseded by a cheaper way to the same state.
+        let i = loop {
+            let Reverse((_, i)) = self.heap.pop()?;
+            if self.tree.is_best(i) {
+                break i;
+            }
+        };
+        let Node { state, cost, .. } = &self.tree.nodes[i];
+        let (state, cost) = (state.clone(), *cost);
+
+        for (next, step) in (self.successors)(&state) {
+            let next_cost = cost + step;
+            let priority = next_cost + (self.heuristic)(&next);
+            if let Some(j) = self.tree.insert(next, next_cost, Some(i)) {
+                self.heap.push(Reverse((priority, j)));
+            }
+        }
+        Some(Visit { state, cost })
+    }
+}
+
+/// The shortest path from `start` to any state matching `is_goal`, where every
+/// step costs 1.
+pub fn bfs<S, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut search = Bfs::new(start, successors);
+    let goal = search.by_ref().find(|visit| is_goal(&visit.state))?;
+    search.path(&goal.state)
+}
+
+/// The cheapest path from `start` to any state matching `is_goal`.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = BestFirst::dijkstra(start, successors);
+    let goal = search.by_ref().find(|visit| is_goal(&visit.state))?;
+    search.path(&goal.state)
+}
+
+/// The cheapest path from `start` to any state matching `is_goal`, guided by a
+/// heuristic that never overestimates the remaining cost.
+pub fn astar<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut search = BestFirst::astar(start, successors, heuristic);
+    let goal = search.by_ref().find(|visit| is_goal(&visit.state))?;
+    search.path(&goal.state)
+}
+
+/// The most expensive path from `start` to any state matching `is_goal` that
+/// never visits the same state twice.
+///
+/// This tries every path, so the graph should be small, e.g. after compressing
+/// corridors into single weighted edges. Paths stop at the first goal they reach.
+pub fn longest_path<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    struct Dfs<'a, S, C, I> {
+        successors: &'a mut dyn FnMut(&S) -> I,
+        is_goal: &'a mut dyn FnMut(&S) -> bool,
+        path: Vec<S>,
+        visited: HashSet<S>,
+        best: Option<Path<S, C>>,
+    }
+
+    impl<S, C, I> Dfs<'_, S, C, I>
+    where
+        S: Clone + Eq + Hash,
+        C: Cost,
+        I: IntoIterator<Item = (S, C)>,
+    {
+        fn visit(&mut self, state: S, cost: C) {
+            if !self.visited.insert(state.clone()) {
+                return;
+            }
+            self.path.push(state.clone());
+
+            if (self.is_goal)(&state) {
+                if self.best.as_ref().is_none_or(|best| cost > best.cost) {
+                    self.best = Some(Path {
+                        cost,
+                        states: self.path.clone(),
+                    });
+                }
+            } else {
+                let next: Vec<_> = (self.successors)(&state).into_iter().collect();
+                for (next, step) in next {
+                    self.visit(next, cost + step);
+                }
+            }
+
+            self.path.pop();
+            self.visited.remove(&state);
+        }
+    }
+
+    let mut dfs = Dfs {
+        successors: &mut successors,
+        is_goal: &mut is_goal,
+        path: Vec::new(),
+        visited: HashSet::new(),
+        best: None,
+    };
+    dfs.visit(start, C::default());
+    dfs.best
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{Direction, Grid, Point};
+
+    const MAZE: &str = "\
+S..#
+.#..
+...#
+#.E.";
+
+    fn maze() -> (Grid<char>, Point, Point) {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let start = grid.position(|&c| c == 'S').unwrap();
+        let end = grid.position(|&c| c == 'E').unwrap();
+        (grid, start, end)
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (grid, start, end) = maze();
+        let open = |p: &Point| -> Vec<Point> {
+            grid.neighbours4(*p).filter(|&n| grid[n] != '#').collect()
+        };
+
+        let path = bfs(start, open, |&p| p == end).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.states.len(), 6);
+        assert_eq!(path.states.first(), Some(&start));
+        assert_eq!(path.states.last(), Some(&end));
+
+        assert_eq!(bfs(start, open, |&p| grid[p] == '#'), None);
+    }
+
+    #[test]
+    fn test_step_by_step() {
+        let (grid, start, _) = maze();
+        let mut search = Bfs::new(start, |p: &Point| {
+            grid.neighbours4(*p)
+                .filter(|&n| grid[n] != '#')
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(search.frontier().collect::<Vec<_>>(), vec![&start]);
+        let first = search.next().unwrap();
+        assert_eq!(
+            first,
+            Visit {
+                state: start,
+                cost: 0
+            }
+        );
+        assert_eq!(search.frontier().count(), 2);
+
+        // Every open cell is visited once, in order of distance.
+        let costs: Vec<_> = search.map(|visit| visit.cost).collect();
+        assert_eq!(costs.len(), 11);
+        assert!(costs.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    /// A crucible that must turn after at most three steps in a straight line,
+    /// where each cell costs its digit to enter.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Crucible {
+        position: Point,
+        direction: Direction,
+        run: u8,
+    }
+
+    const CITY: &str = "\
+24134
+32154
+32552
+34465";
+
+    fn crucible_moves(grid: &Grid<u32>, c: &Crucible) -> Vec<(Crucible, u32)> {
+        [
+            c.direction,
+            c.direction.turn_left(),
+            c.direction.turn_right(),
+        ]
+        .into_iter()
+        .filter_map(|direction| {
+            let run = if direction == c.direction {
+                c.run + 1
+            } else {
+                1
+            };
+            let position = c.position.step(direction);
+            let cost = *grid.get(position)?;
+            (run <= 3).then_some((
+                Crucible {
+                    position,
+                    direction,
+                    run,
+                },
+                cost,
+            ))
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let grid = Grid::parse_with(CITY, |c| Ok(c.to_digit(10).unwrap())).unwrap();
+        let end = Point::new(grid.width() as i64 - 1, grid.height() as i64 - 1);
+        let start = Crucible {
+            position: Point::ORIGIN,
+            direction: Direction::Right,
+            run: 0,
+        };
+
+        let path = dijkstra(
+            start.clone(),
+            |c| crucible_moves(&grid, c),
+            |c| c.position == end,
+        )
+        .unwrap();
+        assert_eq!(path.states.first(), Some(&start));
+        assert_eq!(path.states.last().unwrap().position, end);
+        let total: u32 = path.states[1..].iter().map(|c| grid[c.position]).sum();
+        assert_eq!(total, path.cost);
+
+        let heuristic = |c: &Crucible| c.position.manhattan_distance(end) as u32;
+        let astar_path = astar(
+            start.clone(),
+            |c| crucible_moves(&grid, c),
+            heuristic,
+            |c| c.position == end,
+        )
+        .unwrap();
+        assert_eq!(astar_path.cost, path.cost);
+
+        // A* settles no more states than Dijkstra on the way to the goal.
+        let settled = |mut search: Box<dyn Iterator<Item = Visit<Crucible, u32>> + '_>| {
+            search.position(|v| v.state.position == end).unwrap()
+        };
+        let dijkstra_settled = settled(Box::new(BestFirst::dijkstra(start.clone(), |c| {
+            crucible_moves(&grid, c)
+        })));
+        let astar_settled = settled(Box::new(BestFirst::astar(
+            start,
+            |c| crucible_moves(&grid, c),
+            heuristic,
+        )));
+        assert!(astar_settled <= dijkstra_settled);
+    }
+
+    #[test]
+    fn test_dijkstra_prefers_cheaper_detour() {
+        // a -> c directly costs 10, but going via b costs 3.
+        let edges = |s: &char| -> Vec<(char, u32)> {
+            match s {
+                'a' => vec![('c', 10), ('b', 1)],
+                'b' => vec![('c', 2)],
+                _ => vec![],
+            }
+        };
+        let path = dijkstra('a', edges, |&s| s == 'c').unwrap();
+        assert_eq!(
+            path,
+            Path {
+                cost: 3,
+                states: vec!['a', 'b', 'c']
+            }
+        );
+    }
+
+    #[test]
+    fn test_longest_path() {
+        let edges = |s: &char| -> Vec<(char, u32)> {
+            match s {
+                'a' => vec![('b', 1), ('c', 5)],
+                'b' => vec![('c', 1), ('d', 10)],
+                'c' => vec![('b', 1), ('d', 1)],
+                _ => vec![],
+            }
+        };
+        let path = longest_path('a', edges, |&s| s == 'd').unwrap();
+        assert_eq!(path.cost, 16);
+        assert_eq!(path.states, vec!['a', 'c', 'b', 'd']);
+        assert_eq!(
+            longest_path('a', edges, |&s| s == 'z'),
+            None::<Path<char, u32>>
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! The [`Solution`] trait, and a [`Registry`] mapping puzzle days to their solutions.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::Answer;
+
+/// A day of the Advent of Code calendar, from 1 to 25 inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Day(u8);
+
+impl Day {
+    /// The first day of the calendar.
+    pub const FIRST: Day = Day(1);
+    /// The last day of the calendar.
+    pub const LAST: Day = Day(25);
+
+    /// Create a new day, returning `None` if `day` is not in `1..=25`.
+    pub const fn new(day: u8) -> Option<Self> {
+        if day >= Self::FIRST.0 && day <= Self::LAST.0 {
+            Some(Self(day))
+        } else {
+            None
+        }
+    }
+
+    /// Get the day of the month this day represents.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Iterate over every day in the calendar, in order.
+    pub fn all() -> impl DoubleEndedIterator<Item = Day> + ExactSizeIterator {
+        (Self::FIRST.0..=Self::LAST.0).map(Day)
+    }
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl TryFrom<u8> for Day {
+    type Error = eyre::Report;
+
+    fn try_from(day: u8) -> Result<Self> {
+        Self::new(day).ok_or_else(|| eyre!("Day {day} is not between 1 and 25"))
+    }
+}
+
+impl From<Day> for u8 {
+    fn from(day: Day) -> Self {
+        day.0
+    }
+}
+
+impl FromStr for Day {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let day: u8 = s
+            .trim()
+            .parse()
+            .wrap_err_with(|| format!("{s:?} is not a valid day"))?;
+        day.try_into()
+    }
+}
+
+/// One of the two parts of a day's puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    /// Both parts, in order.
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    /// Get the part number, either 1 or 2.
+    pub const fn get(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = eyre::Report;
+
+    fn try_from(part: u8) -> Result<Self> {
+        match part {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(eyre!("Part {part} is not either 1 or 2")),
+        }
+    }
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> Self {
+        part.get()
+    }
+}
+
+impl FromStr for Part {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let part: u8 = s
+            .trim()
+            .parse()
+            .wrap_err_with(|| format!("{s:?} is not a valid part"))?;
+        part.try_into()
+    }
+}
+
+/// A solution to one day's puzzle.
+///
+/// The input is parsed once with [`Solution::parse`], and then handed to each part.
+pub trait Solution: Send + Sync + 'static {
+    /// The puzzle's title, e.g. `"Trebuchet?!"`.
+    const TITLE: &'static str;
+
+    /// The parsed puzzle input.
+    type Input;
+    /// The answer to part 1.
+    type Answer1: Into<Answer>;
+    /// The answer to part 2.
+    type Answer2: Into<Answer>;
+
+    /// Parse the raw puzzle input.
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+
+    /// Solve part 1 of the puzzle.
+    fn part1(&self, input: &Self::Input) -> Result<Self::Answer1>;
+
+    /// Solve part 2 of the puzzle.
+    fn part2(&self, input: &Self::Input) -> Result<Self::Answer2>;
+}
+
+/// The result of running one part of a [`Solution`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOutput {
+    pub answer: Answer,
+    /// How long it took to parse the input.
+    pub parse_time: Duration,
+    /// How long it took to solve the part, not including parsing.
+    pub solve_time: Duration,
+}
+
+/// An object-safe, type-erased version of [`Solution`].
+///
+/// This is implemented for every [`Solution`], and is what the [`Registry`] stores.
+pub trait DynSolution: Send + Sync {
+    /// The puzzle's title.
+    fn title(&self) -> &'static str;
+
+    /// Parse `input` and solve one part of the puzzle with it.
+    fn run(&self, part: Part, input: &str) -> Result<RunOutput>;
+}
+
+impl<S: Solution> DynSolution for S {
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn run(&self, part: Part, input: &str) -> Result<RunOutput> {
+        let start = Instant::now();
+        let parsed = self.parse(input).wrap_err("Error parsing puzzle input")?;
+        let parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let answer = match part {
+            Part::One => self.part1(&parsed).map(Into::into),
+            Part::Two => self.part2(&parsed).map(Into::into),
+        }
+        .wrap_err_with(|| format!("Error solving part {part}"))?;
+        let solve_time = start.elapsed();
+
+        Ok(RunOutput {
+            answer,
+            parse_time,
+            solve_time,
+        })
+    }
+}
+
+/// One part of a registered solution, as returned by [`Registry::get`].
+#[derive(Clone, Copy)]
+pub struct PartSolution<'a> {
+    pub day: Day,
+    pub part: Part,
+    pub solution: &'a dyn DynSolution,
+}
+
+impl PartSolution<'_> {
+    /// Run this part of the solution against `input`.
+    pub fn run(&self, input: &str) -> Result<RunOutput> {
+        self.solution
+            .run(self.part, input)
+            .wrap_err_with(|| format!("Error running day {} part {}", self.day, self.part))
+    }
+}
+
+impl fmt::Debug for PartSolution<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartSolution")
+            .field("day", &self.day)
+            .field("part", &self.part)
+            .field("title", &self.solution.title())
+            .finish()
+    }
+}
+
+/// Maps each day (and each of its parts) to its solution.
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<Day, Box<dyn DynSolution>>,
+}
+
+impl Registry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the solution for a day.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a solution has already been registered for `day`.
+    pub fn register<S: Solution>(mut self, day: Day, solution: S) -> Self {
+        if self.solutions.insert(day, Box::new(solution)).is_some() {
+            panic!("a solution for day {day} has already been registered");
+        }
+        self
+    }
+
+    /// Get the solution for one part of a day, if that day has been registered.
+    pub fn get(&self, day: Day, part: Part) -> Option<PartSolution<'_>> {
+        self.solutions.get(&day).map(|solution| PartSolution {
+            day,
+            part,
+            solution: solution.as_ref(),
+        })
+    }
+
+    /// Get the title of a registered day's puzzle.
+    pub fn title(&self, day: Day) -> Option<&'static str> {
+        self.solutions.get(&day).map(|solution| solution.title())
+    }
+
+    /// Check if a solution has been registered for `day`.
+    pub fn contains(&self, day: Day) -> bool {
+        self.solutions.contains_key(&day)
+    }
+
+    /// Iterate over every registered day, in order.
+    pub fn days(&self) -> impl Iterator<Item = Day> + '_ {
+        self.solutions.keys().copied()
+    }
+
+    /// Iterate over every part of every registered day, in order.
+    pub fn parts(&self) -> impl Iterator<Item = PartSolution<'_>> + '_ {
+        self.days()
+            .flat_map(move |day| Part::ALL.map(move |part| self.get(day, part).unwrap()))
+    }
+
+    /// The number of registered days.
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    /// Check if no days have been registered.
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.solutions.iter().map(|(day, s)| (day, s.title())))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Sums and multiplies a list of numbers.
+    struct Arithmetic;
+
+    impl Solution for Arithmetic {
+        type Answer1 = u64;
+        type Answer2 = u64;
+        type Input = Vec<u64>;
+
+        const TITLE: &'static str = "Arithmetic";
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            input
+                .lines()
+                .map(|l| l.parse().wrap_err_with(|| format!("bad number {l:?}")))
+                .collect()
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+            Ok(input.iter().product())
+        }
+    }
+
+    fn registry() -> Registry {
+        Registry::new().register(Day::new(3).unwrap(), Arithmetic)
+    }
+
+    #[test]
+    fn test_day_bounds() {
+        assert_eq!(Day::new(0), None);
+        assert_eq!(Day::new(1), Some(Day::FIRST));
+        assert_eq!(Day::new(25), Some(Day::LAST));
+        assert_eq!(Day::new(26), None);
+        assert_eq!(Day::all().count(), 25);
+        assert!("26".parse::<Day>().is_err());
+        assert_eq!("7".parse::<Day>().unwrap().get(), 7);
+    }
+
+    #[test]
+    fn test_part_parsing() {
+        assert_eq!("1".parse::<Part>().unwrap(), Part::One);
+        assert_eq!("2".parse::<Part>().unwrap(), Part::Two);
+        assert!("3".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        let registry = registry();
+        let day = Day::new(3).unwrap();
+
+        assert_eq!(registry.days().collect::<Vec<_>>(), vec![day]);
+        assert_eq!(registry.title(day), Some("Arithmetic"));
+        assert!(registry.get(Day::new(4).unwrap(), Part::One).is_none());
+
+        let part1 = registry.get(day, Part::One).unwrap();
+        assert_eq!(part1.run("2\n3\n4").unwrap().answer, "9");
+        let part2 = registry.get(day, Part::Two).unwrap();
+        assert_eq!(part2.run("2\n3\n4").unwrap().answer, "24");
+
+        assert_eq!(registry.parts().count(), 2);
+    }
+
+    #[test]
+    fn test_registry_parse_error() {
+        let registry = registry();
+        let part1 = registry.get(Day::new(3).unwrap(), Part::One).unwrap();
+        let err = part1.run("2\nthree").unwrap_err();
+        assert!(format!("{err:?}").contains("bad number \"three\""));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_registry_duplicate_day() {
+        let _ = registry().register(Day::new(3).unwrap(), Arithmetic);
+    }
+}
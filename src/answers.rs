@@ -0,0 +1,188 @@
+//! The accepted answer for every solved part, used to catch regressions.
+//!
+//! Answers are stored as TOML in the config directory, so they can be checked in
+//! or edited by hand:
+//!
+//! ```toml
+//! [day01]
+//! part1 = "142"
+//! part2 = "281"
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use aoc2023_common::{Day, Part};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::get_config_dir;
+
+/// The name of the known answers file inside the config directory.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// The answers for both parts of a day, as stored in the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+impl DayAnswers {
+    fn get(&self, part: Part) -> Option<&String> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self, part: Part) -> &mut Option<String> {
+        match part {
+            Part::One => &mut self.part1,
+            Part::Two => &mut self.part2,
+        }
+    }
+}
+
+/// Every known answer, backed by a TOML file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownAnswers {
+    path: PathBuf,
+    answers: BTreeMap<Day, DayAnswers>,
+}
+
+impl KnownAnswers {
+    /// The default location of the known answers, inside the config directory.
+    pub fn default_path() -> PathBuf {
+        get_config_dir().join(ANSWERS_FILE)
+    }
+
+    /// Load the known answers from `path`, starting with none if it doesn't exist yet.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let answers = match fs::read_to_string(&path) {
+            Ok(text) => parse(&text)
+                .wrap_err_with(|| format!("Error parsing known answers {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                return Err(e)
+                    .wrap_err_with(|| format!("Error reading known answers {}", path.display()))
+            },
+        };
+        Ok(Self { path, answers })
+    }
+
+    /// The file these answers are stored in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The known answer for one part of a day.
+    pub fn get(&self, day: Day, part: Part) -> Option<&str> {
+        self.answers.get(&day)?.get(part).map(String::as_str)
+    }
+
+    /// Every known answer, in order of day and part.
+    pub fn iter(&self) -> impl Iterator<Item = (Day, Part, &str)> + '_ {
+        self.answers.iter().flat_map(|(&day, answers)| {
+            Part::ALL
+                .into_iter()
+                .filter_map(move |part| Some((day, part, answers.get(part)?.as_str())))
+        })
+    }
+
+    /// The number of known answers.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Whether there are no known answers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Set the answer for one part of a day, and save the file.
+    pub fn record(&mut self, day: Day, part: Part, answer: impl Into<String>) -> Result<()> {
+        *self.answers.entry(day).or_default().get_mut(part) = Some(answer.into());
+        self.save()
+    }
+
+    /// Write the answers to disk.
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("Error creating directory {}", parent.display()))?;
+        }
+        let table: BTreeMap<String, &DayAnswers> = self
+            .answers
+            .iter()
+            .map(|(day, answers)| (format!("day{:02}", day.get()), answers))
+            .collect();
+        fs::write(&self.path, toml::to_string(&table)?)
+            .wrap_err_with(|| format!("Error writing known answers {}", self.path.display()))
+    }
+}
+
+/// Parse the file's `[dayNN]` tables.
+fn parse(text: &str) -> Result<BTreeMap<Day, DayAnswers>> {
+    let table: BTreeMap<String, DayAnswers> = toml::from_str(text)?;
+    table
+        .into_iter()
+        .map(|(key, answers)| {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(|| eyre!("Expected a table named like [day01], found [{key}]"))?;
+            Ok((day, answers))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn day(day: u8) -> Day {
+        Day::new(day).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join(ANSWERS_FILE);
+
+        let mut answers = KnownAnswers::load(&path).unwrap();
+        assert!(answers.is_empty());
+        answers.record(day(12), Part::Two, "525152").unwrap();
+        answers.record(day(1), Part::One, "142").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[day01]\npart1 = \"142\"\n\n[day12]\npart2 = \"525152\"\n"
+        );
+
+        let loaded = KnownAnswers::load(&path).unwrap();
+        assert_eq!(loaded, answers);
+        assert_eq!(loaded.get(day(1), Part::One), Some("142"));
+        assert_eq!(loaded.get(day(1), Part::Two), None);
+        assert_eq!(
+            loaded.iter().collect::<Vec<_>>(),
+            vec![(day(1), Part::One, "142"), (day(12), Part::Two, "525152")]
+        );
+    }
+
+    #[test]
+    fn test_invalid_table_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(ANSWERS_FILE);
+        fs::write(&path, "[monday]\npart1 = \"1\"\n").unwrap();
+        assert!(KnownAnswers::load(&path).is_err());
+    }
+}
@@ -4,6 +4,7 @@ use color_eyre::eyre::{Result, WrapErr};
 use futures::{prelude::*, stream_select};
 use ratatui::prelude::Rect;
 use tokio::sync::{broadcast, mpsc::channel};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     command::{self, process_cmd},
@@ -23,7 +24,6 @@ pub struct App {
     /// Allows for terminating background threads.
     terminator: Terminator,
     /// Receiver for termination messages from the main thread.
-    #[allow(unused)]
     termination_rx: broadcast::Receiver<Interrupted>,
 }
 
@@ -53,7 +53,18 @@ impl App {
 
         let (msg_tx, msg_rx) = channel::<Message>(1);
 
-        let (init_model, init_cmd) = model::init(&tui);
+        // Stop any running solutions as soon as the app is told to terminate.
+        let shutdown = CancellationToken::new();
+        tokio::spawn({
+            let shutdown = shutdown.clone();
+            let mut termination_rx = self.termination_rx.resubscribe();
+            async move {
+                let _ = termination_rx.recv().await;
+                shutdown.cancel();
+            }
+        });
+
+        let (init_model, init_cmd) = model::init(&tui, shutdown);
 
         command::process_cmd(init_cmd, msg_tx.clone());
 
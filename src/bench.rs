@@ -0,0 +1,266 @@
+//! Benchmark results, stored per commit in the data directory.
+//!
+//! Each commit's results live in their own JSON file named after
+//! [`GIT_COMMIT_HASH`](crate::utils::GIT_COMMIT_HASH), so timings can be
+//! compared with an earlier commit to spot regressions.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use aoc2023_common::{
+    bench::bench, runtime, BenchOptions, BenchResult, Day, InputStore, Part, Registry,
+};
+use color_eyre::eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::get_data_dir;
+
+/// The name of the directory inside the data directory that holds benchmark results.
+pub const BENCH_DIR: &str = "bench";
+
+/// Every benchmark result for one commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub commit: String,
+    /// When the report was last updated, in seconds since the Unix epoch.
+    pub updated_at: u64,
+    /// Results sorted by day and part.
+    pub results: Vec<BenchResult>,
+}
+
+impl BenchReport {
+    /// An empty report for `commit`.
+    pub fn new(commit: impl Into<String>) -> Self {
+        Self {
+            commit: commit.into(),
+            updated_at: 0,
+            results: Vec::new(),
+        }
+    }
+
+    /// The result for one part of a day, if it has been benchmarked.
+    pub fn get(&self, day: Day, part: Part) -> Option<&BenchResult> {
+        self.results
+            .iter()
+            .find(|result| result.day == day && result.part == part)
+    }
+
+    /// Add a result, replacing any earlier result for the same part.
+    pub fn insert(&mut self, result: BenchResult) {
+        match self
+            .results
+            .binary_search_by_key(&(result.day, result.part), |r| (r.day, r.part))
+        {
+            Ok(i) => self.results[i] = result,
+            Err(i) => self.results.insert(i, result),
+        }
+        self.updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+    }
+}
+
+/// A directory of [`BenchReport`]s, one per commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchStore {
+    dir: PathBuf,
+}
+
+impl Default for BenchStore {
+    fn default() -> Self {
+        Self::new(get_data_dir())
+    }
+}
+
+impl BenchStore {
+    /// Store reports in the `bench/` subdirectory of `data_dir`.
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: data_dir.as_ref().join(BENCH_DIR),
+        }
+    }
+
+    /// The file the report for `commit` is stored in.
+    pub fn path(&self, commit: &str) -> PathBuf {
+        let name: String = commit
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Load the report for `commit`, or an empty one if it hasn't been benchmarked.
+    pub fn load(&self, commit: &str) -> Result<BenchReport> {
+        let path = self.path(commit);
+        match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)
+                .wrap_err_with(|| format!("Error parsing benchmark results {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BenchReport::new(commit)),
+            Err(e) => Err(e)
+                .wrap_err_with(|| format!("Error reading benchmark results {}", path.display())),
+        }
+    }
+
+    /// Write a report to disk.
+    pub fn save(&self, report: &BenchReport) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .wrap_err_with(|| format!("Error creating directory {}", self.dir.display()))?;
+        let path = self.path(&report.commit);
+        let json = serde_json::to_string_pretty(report)?;
+        fs::write(&path, json)
+            .wrap_err_with(|| format!("Error writing benchmark results {}", path.display()))
+    }
+
+    /// The most recently updated report for any commit other than `commit`.
+    pub fn previous(&self, commit: &str) -> Result<Option<BenchReport>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e)
+                    .wrap_err_with(|| format!("Error reading directory {}", self.dir.display()))
+            },
+        };
+
+        let mut previous: Option<BenchReport> = None;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let json = fs::read_to_string(&path)
+                .wrap_err_with(|| format!("Error reading benchmark results {}", path.display()))?;
+            let Ok(report) = serde_json::from_str::<BenchReport>(&json) else {
+                continue;
+            };
+            if report.commit != commit
+                && previous
+                    .as_ref()
+                    .is_none_or(|p| report.updated_at > p.updated_at)
+            {
+                previous = Some(report);
+            }
+        }
+        Ok(previous)
+    }
+}
+
+/// Benchmark every registered part, or just those for `day`, saving the results
+/// to `store` under `commit` as they come in.
+///
+/// Days whose input hasn't been downloaded are skipped, so one missing input
+/// doesn't stop the rest being benchmarked.
+///
+/// Reports progress and checks for cancellation through [`runtime`].
+pub fn bench_all(
+    registry: &Registry,
+    inputs: &InputStore,
+    store: &BenchStore,
+    commit: &str,
+    day: Option<Day>,
+    options: BenchOptions,
+) -> Result<BenchReport> {
+    let parts: Vec<_> = registry
+        .parts()
+        .filter(|solution| day.is_none_or(|day| solution.day == day))
+        .collect();
+
+    let mut report = store.load(commit)?;
+    for (i, &solution) in parts.iter().enumerate() {
+        runtime::progress(i, parts.len());
+        if !inputs.contains(solution.day) {
+            tracing::warn!(day = %solution.day, "Skipping benchmark, as there's no puzzle input");
+            continue;
+        }
+        let input = inputs.load(solution.day)?;
+        report.insert(bench(solution, &input.text, options)?);
+        store.save(&report)?;
+    }
+    runtime::progress(parts.len(), parts.len());
+
+    Ok(report)
+}
+
+/// The percentage change from `old` to `new`, or `None` if `old` is zero.
+pub fn change(old: Duration, new: Duration) -> Option<f64> {
+    (!old.is_zero()).then(|| (new.as_secs_f64() / old.as_secs_f64() - 1.0) * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2023_common::bench::Stats;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn result(day: u8, part: Part, median_ms: u64) -> BenchResult {
+        let stats = Stats::from_samples(&[Duration::from_millis(median_ms)]).unwrap();
+        BenchResult {
+            day: Day::new(day).unwrap(),
+            part,
+            runs: 1,
+            parse: stats,
+            solve: stats,
+            answer: None,
+        }
+    }
+
+    #[test]
+    fn test_insert_replaces_and_sorts() {
+        let mut report = BenchReport::new("abc");
+        report.insert(result(3, Part::One, 1));
+        report.insert(result(1, Part::Two, 2));
+        report.insert(result(3, Part::One, 5));
+
+        assert_eq!(
+            report.results,
+            vec![result(1, Part::Two, 2), result(3, Part::One, 5)]
+        );
+        assert_eq!(
+            report.get(Day::new(3).unwrap(), Part::One),
+            Some(&result(3, Part::One, 5))
+        );
+    }
+
+    #[test]
+    fn test_store_round_trip_and_previous() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BenchStore::new(dir.path());
+        assert_eq!(
+            store.load("v0.1.0-1-abc").unwrap(),
+            BenchReport::new("v0.1.0-1-abc")
+        );
+        assert_eq!(store.previous("v0.1.0-1-abc").unwrap(), None);
+
+        let mut old = BenchReport::new("v0.1.0-1-abc");
+        old.insert(result(1, Part::One, 10));
+        store.save(&old).unwrap();
+
+        let mut new = BenchReport::new("v0.1.0-2-def/dirty");
+        new.insert(result(1, Part::One, 5));
+        new.updated_at = old.updated_at + 1;
+        store.save(&new).unwrap();
+
+        assert_eq!(store.load(&new.commit).unwrap(), new);
+        assert_eq!(store.previous(&new.commit).unwrap(), Some(old.clone()));
+        assert_eq!(store.previous(&old.commit).unwrap(), Some(new));
+    }
+
+    #[test]
+    fn test_change() {
+        let ms = Duration::from_millis;
+        assert_eq!(change(ms(10), ms(15)), Some(50.0));
+        assert_eq!(change(ms(10), ms(5)), Some(-50.0));
+        assert_eq!(change(Duration::ZERO, ms(5)), None);
+    }
+}
@@ -0,0 +1,294 @@
+use aoc2023_common::{BenchOptions, BenchResult};
+use crossterm::event::{KeyCode, KeyEvent};
+use futures::prelude::*;
+use ratatui::{prelude::*, widgets::*};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    bench::{bench_all, change, BenchReport},
+    command::Cmd,
+    message::Message,
+    model::Model,
+    notification::{Notification, NotificationMessage},
+    runner::{self, RunEvent},
+    tui::Frame,
+    utils::GIT_COMMIT_HASH,
+};
+
+/// A table of benchmark results for the current commit.
+#[derive(Debug, Default)]
+pub struct BenchResultsModel {
+    /// The results for the current commit.
+    report: Option<BenchReport>,
+    /// The results for the most recently benchmarked other commit, to compare against.
+    previous: Option<BenchReport>,
+    /// Set while a benchmark is running.
+    running: Option<Running>,
+}
+
+#[derive(Debug)]
+struct Running {
+    cancel: CancellationToken,
+    progress: Option<f32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BenchResultsMessage {
+    /// Benchmark every registered part.
+    Run,
+    /// Stop the running benchmark.
+    Cancel,
+    /// The running benchmark has made progress or finished.
+    Event(RunEvent<BenchReport>),
+}
+
+pub fn handle_key(_model: &Model, key: KeyEvent) -> Option<Message> {
+    let msg = match key.code {
+        KeyCode::Char('r') => BenchResultsMessage::Run,
+        KeyCode::Char('c') => BenchResultsMessage::Cancel,
+        _ => return None,
+    };
+    Some(Message::BenchResultsMessage(msg))
+}
+
+/// Load the saved results when the screen is shown.
+pub fn open(mut model: Model) -> (Model, Cmd<Message>) {
+    let loaded = model
+        .bench_store
+        .load(GIT_COMMIT_HASH)
+        .and_then(|report| Ok((report, model.bench_store.previous(GIT_COMMIT_HASH)?)));
+
+    match loaded {
+        Ok((report, previous)) => {
+            model.bench_results.report = Some(report);
+            model.bench_results.previous = previous;
+            (model, Cmd::None)
+        },
+        Err(e) => show_error(model, format!("{e:#}")),
+    }
+}
+
+pub fn update(mut model: Model, msg: BenchResultsMessage) -> (Model, Cmd<Message>) {
+    let results_model = &mut model.bench_results;
+    match msg {
+        BenchResultsMessage::Run => {
+            if results_model.running.is_some() {
+                return (model, Cmd::None);
+            }
+            let cancel = model.shutdown.child_token();
+            results_model.running = Some(Running {
+                cancel: cancel.clone(),
+                progress: None,
+            });
+
+            let registry = model.registry.clone();
+            let inputs = model.client.inputs().clone();
+            let store = model.bench_store.clone();
+            let events = runner::spawn(cancel, move || {
+                bench_all(
+                    &registry,
+                    &inputs,
+                    &store,
+                    GIT_COMMIT_HASH,
+                    None,
+                    BenchOptions::default(),
+                )
+            });
+            return (
+                model,
+                Cmd::stream(
+                    events.map(|event| {
+                        Message::BenchResultsMessage(BenchResultsMessage::Event(event))
+                    }),
+                ),
+            );
+        },
+
+        BenchResultsMessage::Cancel => {
+            if let Some(running) = &results_model.running {
+                running.cancel.cancel();
+            }
+        },
+
+        BenchResultsMessage::Event(RunEvent::Progress(progress)) => {
+            if let Some(running) = &mut results_model.running {
+                running.progress = Some(progress);
+            }
+        },
+
+        BenchResultsMessage::Event(RunEvent::Finished(result)) => {
+            results_model.running = None;
+            match result {
+                Ok(report) => results_model.report = Some(report),
+                Err(e) => return show_error(model, e.summary()),
+            }
+        },
+
+        BenchResultsMessage::Event(RunEvent::Cancelled) => {
+            results_model.running = None;
+            // Results are saved as they come in, so show whatever was finished.
+            return open(model);
+        },
+    }
+    (model, Cmd::None)
+}
+
+fn show_error(model: Model, text: String) -> (Model, Cmd<Message>) {
+    (
+        model,
+        Cmd::Msg(Message::NotificationMessage(NotificationMessage::Show(
+            Notification::Error(text),
+        ))),
+    )
+}
+
+pub fn view(model: &mut Model, f: &mut Frame, area: Rect) {
+    let results_model = &model.bench_results;
+
+    let block = Block::default()
+        .title(
+            block::Title::from(format!("Benchmarks for {GIT_COMMIT_HASH}"))
+                .alignment(Alignment::Center),
+        )
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().dim());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let [status_area, table_area] = *Layout::new(
+        Direction::Vertical,
+        [Constraint::Length(2), Constraint::Min(0)],
+    )
+    .split(inner) else {
+        return;
+    };
+
+    let status = match (&results_model.running, &results_model.previous) {
+        (Some(Running { progress, .. }), _) => Line::from(
+            format!(
+                "Benchmarking... {:.0}%",
+                progress.unwrap_or_default() * 100.0
+            )
+            .fg(Color::Cyan),
+        ),
+        (None, Some(previous)) => {
+            Line::from(format!("Compared with {}", previous.commit).fg(Color::DarkGray))
+        },
+        (None, None) => Line::from("No other commits to compare with".fg(Color::DarkGray)),
+    };
+    f.render_widget(Paragraph::new(status), status_area);
+
+    let results = results_model
+        .report
+        .as_ref()
+        .map_or(&[][..], |report| &report.results[..]);
+    if results.is_empty() {
+        f.render_widget(
+            Paragraph::new("Nothing has been benchmarked yet.".fg(Color::DarkGray)),
+            table_area,
+        );
+        return;
+    }
+
+    let header = Row::new([
+        "Day", "Part", "Runs", "Parse", "Min", "Median", "Mean", "p95", "Change",
+    ])
+    .style(Style::new().bold());
+    let rows = results
+        .iter()
+        .map(|result| row(result, results_model.previous.as_ref()));
+    let widths = [
+        Constraint::Length(4),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(8),
+    ];
+    f.render_widget(Table::new(rows, widths).header(header), table_area);
+}
+
+/// A row of the results table.
+fn row(result: &BenchResult, previous: Option<&BenchReport>) -> Row<'static> {
+    let change = previous
+        .and_then(|previous| previous.get(result.day, result.part))
+        .and_then(|old| change(old.solve.median, result.solve.median));
+    let change = match change {
+        Some(change) => {
+            let color = if change > 5.0 {
+                Color::Red
+            } else if change < -5.0 {
+                Color::Green
+            } else {
+                Color::Gray
+            };
+            Cell::from(format!("{change:+.1}%")).style(Style::new().fg(color))
+        },
+        None => Cell::from(""),
+    };
+
+    Row::new([
+        Cell::from(result.day.to_string()),
+        Cell::from(result.part.to_string()),
+        Cell::from(result.runs.to_string()),
+        Cell::from(format!("{:?}", result.parse.median)),
+        Cell::from(format!("{:?}", result.solve.min)),
+        Cell::from(format!("{:?}", result.solve.median)),
+        Cell::from(format!("{:?}", result.solve.mean)),
+        Cell::from(format!("{:?}", result.solve.p95)),
+        change,
+    ])
+}
+
+pub fn usage() -> Line<'static> {
+    Line::from(vec![
+        "r".bold().fg(Color::Gray),
+        " to run, ".fg(Color::DarkGray),
+        "c".bold().fg(Color::Gray),
+        " to cancel, ".fg(Color::DarkGray),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_run_and_finish() {
+        let (model, cmd) = update(Model::default(), BenchResultsMessage::Run);
+        assert!(matches!(cmd, Cmd::Stream(_)));
+        assert!(model.bench_results.running.is_some());
+
+        // Running again while a benchmark is in progress does nothing.
+        let (model, cmd) = update(model, BenchResultsMessage::Run);
+        assert!(matches!(cmd, Cmd::None));
+
+        let (model, _) = update(model, BenchResultsMessage::Event(RunEvent::Progress(0.5)));
+        assert_eq!(
+            model.bench_results.running.as_ref().unwrap().progress,
+            Some(0.5)
+        );
+
+        let report = BenchReport::new("abc");
+        let (model, _) = update(
+            model,
+            BenchResultsMessage::Event(RunEvent::Finished(Ok(report.clone()))),
+        );
+        assert!(model.bench_results.running.is_none());
+        assert_eq!(model.bench_results.report, Some(report));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let (model, _) = update(Model::default(), BenchResultsMessage::Run);
+        let cancel = model.bench_results.running.as_ref().unwrap().cancel.clone();
+        let (_, _) = update(model, BenchResultsMessage::Cancel);
+        assert!(cancel.is_cancelled());
+    }
+}
@@ -1,4 +1,7 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use aoc2023_common::{Day, Part};
+use clap::{Parser, Subcommand};
 
 use crate::utils::version;
 
@@ -22,4 +25,113 @@ pub struct Cli {
         default_value_t = 30.0
     )]
     pub frame_rate: f64,
+
+    /// Run without the TUI. If no command is given, the TUI is launched.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// Run a day's solution and print the answers.
+    Run {
+        #[arg(short, long, help = "The day to run")]
+        day: Day,
+
+        #[arg(short, long, help = "Only run this part, instead of both")]
+        part: Option<Part>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Read the puzzle input from FILE instead of the data directory"
+        )]
+        input: Option<PathBuf>,
+    },
+
+    /// List every solved day.
+    List,
+
+    /// Time every solved day.
+    Bench {
+        #[arg(short, long, help = "Only time this day")]
+        day: Option<Day>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "N",
+            help = "Number of timed runs of each part",
+            default_value_t = 10
+        )]
+        runs: u32,
+
+        #[arg(
+            short,
+            long,
+            value_name = "N",
+            help = "Number of untimed runs of each part before timing it",
+            default_value_t = 3
+        )]
+        warmup: u32,
+    },
+
+    /// Re-run every part with a known answer, and check the answers haven't changed.
+    Verify {
+        #[arg(short, long, help = "Only verify this day")]
+        day: Option<Day>,
+    },
+
+    /// Download puzzle inputs that haven't been downloaded yet.
+    Fetch {
+        #[arg(
+            short,
+            long,
+            help = "Only fetch the input for this day, instead of for every solved day"
+        )]
+        day: Option<Day>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_cli_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_no_command_launches_tui() {
+        let cli = Cli::try_parse_from(["aoc2023", "--tick-rate", "4"]).unwrap();
+        assert_eq!(cli.command, None);
+        assert_eq!(cli.tick_rate, 4.0);
+    }
+
+    #[test]
+    fn test_run_command() {
+        let cli = Cli::try_parse_from(["aoc2023", "run", "--day", "7", "-p", "2"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Command::Run {
+                day: Day::new(7).unwrap(),
+                part: Some(Part::Two),
+                input: None,
+            })
+        );
+
+        assert!(Cli::try_parse_from(["aoc2023", "run", "--day", "26"]).is_err());
+        assert!(Cli::try_parse_from(["aoc2023", "run", "--day", "1", "--part", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_verify_command() {
+        let cli = Cli::try_parse_from(["aoc2023", "verify"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Verify { day: None }));
+    }
 }
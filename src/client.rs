@@ -0,0 +1,450 @@
+//! A client for the Advent of Code website.
+//!
+//! Downloaded inputs are cached in the [`InputStore`], and are never downloaded
+//! twice. Submitted answers are recorded in the [`Ledger`], and the same wrong
+//! answer is never submitted twice. Every request is rate-limited, and
+//! identifies this app in its `User-Agent` header as requested by the Advent of
+//! Code maintainers.
+
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use aoc2023_common::{Day, InputStore, Part, PuzzleInput};
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use lazy_static::lazy_static;
+use reqwest::{header, StatusCode};
+use tokio::sync::Mutex;
+
+use crate::{
+    ledger::{Attempt, Ledger, SubmitOutcome},
+    utils::{get_config_dir, get_data_dir, BASE_URL},
+};
+
+/// The default website to talk to.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// The name of the file in the config directory holding the session token.
+pub const SESSION_FILE: &str = "session";
+/// The default minimum time between two requests.
+pub const DEFAULT_MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    /// The `User-Agent` to send with every request.
+    pub static ref USER_AGENT: String = format!(
+        "{}/{} ({} by {})",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_REPOSITORY"),
+        env!("CARGO_PKG_AUTHORS").replace(':', ", "),
+    );
+}
+
+/// Talks to the Advent of Code website.
+///
+/// The default client uses the app's data and config directories, and the base
+/// URL from `AOC2023_BASE_URL` if it is set.
+#[derive(Debug, Clone)]
+pub struct AocClient {
+    /// The underlying HTTP client.
+    http: reqwest::Client,
+    /// The website's base URL, without a trailing slash.
+    base_url: String,
+    /// The file containing the session token.
+    session_path: PathBuf,
+    /// Where downloaded inputs are cached.
+    inputs: InputStore,
+    /// Minimum time between two requests.
+    min_request_interval: Duration,
+    /// When the last request was sent, shared between all clones of this client.
+    last_request: Arc<Mutex<Option<Instant>>>,
+    /// One lock per day, held while checking the cache and downloading that
+    /// day's input, so concurrent fetches download it only once.
+    input_locks: Arc<Vec<Mutex<()>>>,
+}
+
+impl Default for AocClient {
+    fn default() -> Self {
+        let client = Self::new(
+            InputStore::new(get_data_dir()),
+            get_config_dir().join(SESSION_FILE),
+        );
+        match BASE_URL.clone() {
+            Some(base_url) => client.base_url(base_url),
+            None => client,
+        }
+    }
+}
+
+impl AocClient {
+    /// Create a new client that caches inputs in `inputs`, and reads its session
+    /// token from `session_path`.
+    pub fn new(inputs: InputStore, session_path: impl Into<PathBuf>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session_path: session_path.into(),
+            inputs,
+            min_request_interval: DEFAULT_MIN_REQUEST_INTERVAL,
+            last_request: Arc::new(Mutex::new(None)),
+            input_locks: Arc::new(Day::all().map(|_| Mutex::new(())).collect()),
+        }
+    }
+
+    /// Set the website's base URL.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Set the minimum time between two requests.
+    pub fn min_request_interval(mut self, min_request_interval: Duration) -> Self {
+        self.min_request_interval = min_request_interval;
+        self
+    }
+
+    /// The store that downloaded inputs are cached in.
+    pub fn inputs(&self) -> &InputStore {
+        &self.inputs
+    }
+
+    /// Get a day's input, downloading it only if it hasn't been cached yet.
+    pub async fn fetch_input(&self, day: Day) -> Result<PuzzleInput> {
+        let _lock = self.input_locks[usize::from(day.get() - 1)].lock().await;
+        if self.inputs.contains(day) {
+            tracing::debug!(%day, "Using cached puzzle input");
+            return self.inputs.load(day);
+        }
+
+        let url = format!("{}/2023/day/{day}/input", self.base_url);
+        tracing::info!(%day, %url, "Downloading puzzle input");
+
+        let response = self
+            .send(self.http.get(&url))
+            .await
+            .wrap_err_with(|| format!("Error downloading input for day {day}"))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .wrap_err_with(|| format!("Error reading input for day {day}"))?;
+
+        match status {
+            StatusCode::OK => self.inputs.save(day, &body),
+            StatusCode::NOT_FOUND => Err(eyre!("Day {day}'s puzzle has not been unlocked yet")),
+            StatusCode::BAD_REQUEST | StatusCode::INTERNAL_SERVER_ERROR
+                if body.contains("log in") =>
+            {
+                Err(eyre!(
+                    "The session token in {} has expired or is invalid",
+                    self.session_path.display()
+                ))
+            },
+            status => Err(eyre!(
+                "Unexpected response {status} while downloading input for day {day}: {}",
+                body.trim()
+            )),
+        }
+    }
+
+    /// Submit an answer for one part of a day, and record the outcome in `ledger`.
+    ///
+    /// Refuses to submit answers for parts that have already been solved, and
+    /// answers that have already been conclusively checked.
+    pub async fn submit_answer(
+        &self,
+        ledger: &mut Ledger,
+        day: Day,
+        part: Part,
+        answer: &str,
+    ) -> Result<SubmitOutcome> {
+        let answer = answer.trim();
+        if answer.is_empty() {
+            bail!("Refusing to submit an empty answer for day {day} part {part}");
+        }
+        if let Some(solution) = ledger.solution(day, part) {
+            bail!(
+                "Day {day} part {part} has already been solved (the answer was {})",
+                solution.answer
+            );
+        }
+        if let Some(previous) = ledger.previous(day, part, answer) {
+            bail!(
+                "{answer} has already been submitted for day {day} part {part}: {}",
+                previous.outcome
+            );
+        }
+
+        let url = format!("{}/2023/day/{day}/answer", self.base_url);
+        tracing::info!(%day, %part, %answer, %url, "Submitting answer");
+
+        let level = part.to_string();
+        let response = self
+            .send(
+                self.http
+                    .post(&url)
+                    .form(&[("level", level.as_str()), ("answer", answer)]),
+            )
+            .await
+            .wrap_err_with(|| format!("Error submitting answer for day {day} part {part}"))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .wrap_err_with(|| format!("Error reading submission response for day {day}"))?;
+
+        if status != StatusCode::OK {
+            bail!(
+                "Unexpected response {status} while submitting answer for day {day} part {part}: {}",
+                body.trim()
+            );
+        }
+
+        let outcome = SubmitOutcome::parse(&body)?;
+        ledger.record(Attempt::now(day, part, answer, outcome.clone()))?;
+        Ok(outcome)
+    }
+
+    /// Read the session token from disk.
+    fn session_token(&self) -> Result<String> {
+        let token = std::fs::read_to_string(&self.session_path).wrap_err_with(|| {
+            format!(
+                "No session token found at {}. Log in to {}, then copy the value of the \
+                 `session` cookie into that file.",
+                self.session_path.display(),
+                self.base_url,
+            )
+        })?;
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(eyre!(
+                "The session token file {} is empty",
+                self.session_path.display()
+            ));
+        }
+        Ok(token.to_string())
+    }
+
+    /// Wait until another request is allowed to be sent.
+    async fn rate_limit(&self) {
+        let mut last_request = self.last_request.lock().await;
+        if let Some(last) = *last_request {
+            let wait = self.min_request_interval.saturating_sub(last.elapsed());
+            if !wait.is_zero() {
+                tracing::debug!(?wait, "Rate-limiting request");
+                tokio::time::sleep(wait).await;
+            }
+        }
+        *last_request = Some(Instant::now());
+    }
+
+    /// Send an authenticated, rate-limited request.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let token = self.session_token()?;
+        self.rate_limit().await;
+        Ok(request
+            .header(header::USER_AGENT, USER_AGENT.as_str())
+            .header(header::COOKIE, format!("session={token}"))
+            .send()
+            .await?)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use pretty_assertions::assert_eq;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// A tiny stand-in for the Advent of Code website.
+    ///
+    /// Responds to every request with `status` and `body`, and records the raw
+    /// text of every request it receives.
+    pub(crate) struct MockServer {
+        pub url: String,
+        pub requests: Arc<std::sync::Mutex<Vec<String>>>,
+        pub hits: Arc<AtomicUsize>,
+    }
+
+    impl MockServer {
+        pub(crate) async fn start(status: u16, body: &'static str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let hits = Arc::new(AtomicUsize::new(0));
+
+            let (task_requests, task_hits) = (requests.clone(), hits.clone());
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let mut buf = vec![0; 8192];
+                    let mut len = 0;
+                    // Read until the end of the headers, and then the body if any.
+                    loop {
+                        let n = socket.read(&mut buf[len..]).await.unwrap();
+                        len += n;
+                        let text = String::from_utf8_lossy(&buf[..len]);
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let content_length = text
+                                .lines()
+                                .find_map(|l| {
+                                    l.to_lowercase()
+                                        .strip_prefix("content-length: ")
+                                        .map(|n| n.trim().parse::<usize>().unwrap())
+                                })
+                                .unwrap_or(0);
+                            if len >= end + 4 + content_length {
+                                break;
+                            }
+                        }
+                        if n == 0 {
+                            break;
+                        }
+                    }
+                    task_requests
+                        .lock()
+                        .unwrap()
+                        .push(String::from_utf8_lossy(&buf[..len]).into_owned());
+                    task_hits.fetch_add(1, Ordering::SeqCst);
+
+                    let response = format!(
+                        "HTTP/1.1 {status} Whatever\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                    socket.shutdown().await.unwrap();
+                }
+            });
+
+            Self {
+                url,
+                requests,
+                hits,
+            }
+        }
+    }
+
+    /// Create a client talking to `server`, with a session token and an empty
+    /// data directory.
+    pub(crate) fn client_setup(server: &MockServer) -> (AocClient, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let session_path = dir.path().join(SESSION_FILE);
+        std::fs::write(&session_path, "s3cr3t\n").unwrap();
+        let client = AocClient::new(InputStore::new(dir.path()), session_path)
+            .base_url(&server.url)
+            .min_request_interval(Duration::ZERO);
+        (client, dir)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_input_and_cache() {
+        let server = MockServer::start(200, "1 2 3\r\n4 5 6\r\n").await;
+        let (client, _dir) = client_setup(&server);
+        let day = Day::new(6).unwrap();
+
+        let input = client.fetch_input(day).await.unwrap();
+        assert_eq!(input.text, "1 2 3\n4 5 6");
+        assert!(client.inputs().contains(day));
+
+        let request = server.requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("GET /2023/day/6/input "), "{request}");
+        assert!(request.contains("cookie: session=s3cr3t\r\n"), "{request}");
+        assert!(
+            request.contains(&format!("user-agent: {}\r\n", *USER_AGENT)),
+            "{request}"
+        );
+
+        // The second fetch should come from the cache.
+        assert_eq!(client.fetch_input(day).await.unwrap(), input);
+        assert_eq!(server.hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetches_download_once() {
+        let server = MockServer::start(200, "1 2 3\n").await;
+        let (client, _dir) = client_setup(&server);
+        let other = client.clone();
+        let day = Day::new(6).unwrap();
+
+        let (a, b) = tokio::join!(client.fetch_input(day), other.fetch_input(day));
+        assert_eq!(a.unwrap(), b.unwrap());
+        assert_eq!(server.hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_locked_day() {
+        let server = MockServer::start(404, "Not found").await;
+        let (client, _dir) = client_setup(&server);
+        let day = Day::new(25).unwrap();
+
+        let err = client.fetch_input(day).await.unwrap_err();
+        assert!(err.to_string().contains("not been unlocked"), "{err}");
+        assert!(!client.inputs().contains(day));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_without_session() {
+        let server = MockServer::start(200, "").await;
+        let (client, dir) = client_setup(&server);
+        std::fs::remove_file(dir.path().join(SESSION_FILE)).unwrap();
+
+        let err = client.fetch_input(Day::FIRST).await.unwrap_err();
+        assert!(
+            format!("{err:?}").contains("No session token found"),
+            "{err:?}"
+        );
+        assert_eq!(server.hits.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let server = MockServer::start(200, "x").await;
+        let (client, _dir) = client_setup(&server);
+        let client = client.min_request_interval(Duration::from_millis(200));
+
+        let start = Instant::now();
+        client.fetch_input(Day::new(1).unwrap()).await.unwrap();
+        client.fetch_input(Day::new(2).unwrap()).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(server.hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_submit_answer() {
+        let server = MockServer::start(
+            200,
+            "<article><p>That's not the right answer; your answer is too low.</p></article>",
+        )
+        .await;
+        let (client, dir) = client_setup(&server);
+        let ledger_path = dir.path().join(crate::ledger::LEDGER_FILE);
+        let mut ledger = Ledger::load(&ledger_path).unwrap();
+        let day = Day::new(8).unwrap();
+
+        let outcome = client
+            .submit_answer(&mut ledger, day, Part::Two, "42")
+            .await
+            .unwrap();
+        assert_eq!(outcome, SubmitOutcome::TooLow);
+
+        let request = server.requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /2023/day/8/answer "), "{request}");
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=42"), "{request}");
+
+        // The same wrong answer should never be sent twice.
+        let err = client
+            .submit_answer(&mut ledger, day, Part::Two, "42")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already been submitted"), "{err}");
+        assert_eq!(server.hits.load(Ordering::SeqCst), 1);
+        assert_eq!(Ledger::load(&ledger_path).unwrap().attempts().len(), 1);
+    }
+}
@@ -4,6 +4,8 @@ use tokio::sync::mpsc::Sender;
 pub enum Cmd<Msg> {
     None,
     Future(future::BoxFuture<'static, Msg>),
+    /// A stream of messages, e.g. progress updates from a long-running task.
+    Stream(stream::BoxStream<'static, Msg>),
     Msg(Msg),
 }
 
@@ -14,6 +16,13 @@ impl<Msg> Cmd<Msg> {
     {
         Self::Future(Box::pin(f))
     }
+
+    pub fn stream<S>(s: S) -> Self
+    where
+        S: Stream<Item = Msg> + Send + 'static,
+    {
+        Self::Stream(Box::pin(s))
+    }
 }
 
 pub fn process_cmd<Msg: Send + 'static>(cmd: Cmd<Msg>, msg_tx: Sender<Msg>) {
@@ -27,6 +36,19 @@ pub fn process_cmd<Msg: Send + 'static>(cmd: Cmd<Msg>, msg_tx: Sender<Msg>) {
             });
         },
 
+        Cmd::Stream(mut stream) => {
+            tokio::spawn(async move {
+                while let Some(msg) = stream.next().await {
+                    // The receiver is dropped when the app quits, which can
+                    // happen part way through a stream, so stop quietly.
+                    if msg_tx.send(msg).await.is_err() {
+                        tracing::debug!("Dropping streaming command, as the app has quit");
+                        break;
+                    }
+                }
+            });
+        },
+
         Cmd::Msg(msg) => {
             tokio::spawn(async move {
                 if let Err(e) = msg_tx.send(msg).await {
@@ -0,0 +1,609 @@
+use std::collections::BTreeMap;
+
+use aoc2023_common::{
+    examples::ExampleOutcome, runtime::RunNotes, Answer, Day, ExampleStore, Part, RunOutput,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use futures::prelude::*;
+use ratatui::{prelude::*, widgets::*};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    command::Cmd,
+    diagnostic::Diagnostic,
+    message::Message,
+    model::Model,
+    notification::{Notification, NotificationMessage},
+    runner::{self, PartRun, RunEvent},
+    submit_dialog::SubmitDialogMessage,
+    tui::Frame,
+    utils::get_puzzles_dir,
+};
+
+/// Details about a single day, with buttons to run each part.
+#[derive(Debug)]
+pub struct DayDetailModel {
+    pub day: Day,
+    pub selected_part: Part,
+    /// Short hash of the day's input, if it has been downloaded.
+    input_hash: Option<String>,
+    /// The latest result of running each part of each day.
+    results: BTreeMap<(Day, Part), PartResult>,
+    /// Tokens for cancelling the parts that are currently running.
+    running: BTreeMap<(Day, Part), CancellationToken>,
+    /// Where to find each day's examples, which are run before the real input.
+    examples: ExampleStore,
+}
+
+impl Default for DayDetailModel {
+    fn default() -> Self {
+        Self {
+            day: Day::FIRST,
+            selected_part: Part::One,
+            input_hash: None,
+            results: BTreeMap::new(),
+            running: BTreeMap::new(),
+            examples: ExampleStore::new(get_puzzles_dir()),
+        }
+    }
+}
+
+/// The latest result of running one part of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartResult {
+    /// Still running, with the latest progress reported by the solution.
+    Running(Option<f32>),
+    /// Finished, after passing this many examples.
+    Done {
+        output: RunOutput,
+        examples: usize,
+        notes: RunNotes,
+    },
+    /// An example failed, so the real input wasn't run.
+    ExampleFailed(ExampleOutcome),
+    /// The run failed, and the error is shown in a pane of its own.
+    Failed(Diagnostic),
+    Cancelled,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DayDetailMessage {
+    /// Select the other part's button.
+    ToggleSelectedPart,
+    /// Run one part of the day.
+    Run(Part),
+    /// Stop every running part of the day.
+    Cancel,
+    /// A running part has made progress or finished.
+    Event {
+        day: Day,
+        part: Part,
+        event: RunEvent,
+    },
+    /// Submit the latest answer for one part of the day.
+    Submit(Part),
+}
+
+pub fn handle_key(model: &Model, key: KeyEvent) -> Option<Message> {
+    let detail_model = &model.day_detail;
+    let msg = match key.code {
+        KeyCode::Char('f') => return Some(Message::FetchInput(detail_model.day)),
+        KeyCode::Left
+        | KeyCode::Right
+        | KeyCode::Char('h')
+        | KeyCode::Char('l')
+        | KeyCode::Tab
+        | KeyCode::BackTab => DayDetailMessage::ToggleSelectedPart,
+        KeyCode::Enter => DayDetailMessage::Run(detail_model.selected_part),
+        KeyCode::Char('1') => DayDetailMessage::Run(Part::One),
+        KeyCode::Char('2') => DayDetailMessage::Run(Part::Two),
+        KeyCode::Char('s') => DayDetailMessage::Submit(detail_model.selected_part),
+        KeyCode::Char('c') => DayDetailMessage::Cancel,
+        _ => return None,
+    };
+    Some(Message::DayDetailMessage(msg))
+}
+
+/// Show the details for `day`.
+pub fn open(mut model: Model, day: Day) -> (Model, Cmd<Message>) {
+    let input_hash = model
+        .client
+        .inputs()
+        .load(day)
+        .ok()
+        .map(|input| input.short_hash().to_string());
+
+    let detail_model = &mut model.day_detail;
+    detail_model.day = day;
+    detail_model.input_hash = input_hash;
+    (model, Cmd::None)
+}
+
+/// Remember that a day's input has been downloaded.
+pub fn input_fetched(mut model: Model, day: Day, short_hash: &str) -> Model {
+    if model.day_detail.day == day {
+        model.day_detail.input_hash = Some(short_hash.to_string());
+    }
+    model
+}
+
+pub fn update(mut model: Model, msg: DayDetailMessage) -> (Model, Cmd<Message>) {
+    let detail_model = &mut model.day_detail;
+    match msg {
+        DayDetailMessage::ToggleSelectedPart => {
+            detail_model.selected_part = match detail_model.selected_part {
+                Part::One => Part::Two,
+                Part::Two => Part::One,
+            };
+        },
+
+        DayDetailMessage::Run(part) => {
+            let day = detail_model.day;
+            if !model.registry.contains(day) {
+                return show_error(model, format!("Day {day} hasn't been solved yet"));
+            }
+            if detail_model.running.contains_key(&(day, part)) {
+                return (model, Cmd::None);
+            }
+            detail_model.selected_part = part;
+            detail_model
+                .results
+                .insert((day, part), PartResult::Running(None));
+
+            // Runs are cancelled along with the rest of the app when it shuts down.
+            let cancel = model.shutdown.child_token();
+            detail_model.running.insert((day, part), cancel.clone());
+
+            let events = runner::run_part(
+                model.registry.clone(),
+                model.client.inputs().clone(),
+                detail_model.examples.clone(),
+                day,
+                part,
+                cancel,
+            );
+            return (
+                model,
+                Cmd::stream(events.map(move |event| {
+                    Message::DayDetailMessage(DayDetailMessage::Event { day, part, event })
+                })),
+            );
+        },
+
+        DayDetailMessage::Cancel => {
+            let day = detail_model.day;
+            for part in Part::ALL {
+                if let Some(cancel) = detail_model.running.get(&(day, part)) {
+                    cancel.cancel();
+                }
+            }
+        },
+
+        DayDetailMessage::Event { day, part, event } => {
+            if event.is_final() {
+                detail_model.running.remove(&(day, part));
+            }
+            let result = match event {
+                RunEvent::Progress(progress) => PartResult::Running(Some(progress)),
+                RunEvent::Finished(Ok(PartRun::Done {
+                    output,
+                    examples,
+                    notes,
+                })) => PartResult::Done {
+                    output,
+                    examples,
+                    notes,
+                },
+                RunEvent::Finished(Ok(PartRun::ExampleFailed(failure))) => {
+                    PartResult::ExampleFailed(failure)
+                },
+                RunEvent::Finished(Err(e)) => PartResult::Failed(e),
+                RunEvent::Cancelled => PartResult::Cancelled,
+            };
+            detail_model.results.insert((day, part), result);
+        },
+
+        DayDetailMessage::Submit(part) => {
+            let day = detail_model.day;
+            let output = match detail_model.results.get(&(day, part)) {
+                Some(PartResult::Done { output, .. }) => output,
+                Some(PartResult::ExampleFailed(_)) => {
+                    return show_error(
+                        model,
+                        format!("Part {part} fails its examples, so it won't be submitted"),
+                    );
+                },
+                _ => return show_error(model, format!("Run part {part} before submitting it")),
+            };
+            let answer = match output.answer.submission() {
+                Ok(answer) => answer,
+                Err(e) => {
+                    return show_error(model, format!("Part {part}'s answer couldn't be read: {e}"))
+                },
+            };
+            return (
+                model,
+                Cmd::Msg(Message::SubmitDialogMessage(SubmitDialogMessage::Open {
+                    day,
+                    part,
+                    answer,
+                })),
+            );
+        },
+    }
+    (model, Cmd::None)
+}
+
+fn show_error(model: Model, text: String) -> (Model, Cmd<Message>) {
+    (
+        model,
+        Cmd::Msg(Message::NotificationMessage(NotificationMessage::Show(
+            Notification::Error(text),
+        ))),
+    )
+}
+
+pub fn view(model: &mut Model, f: &mut Frame, area: Rect) {
+    let detail_model = &model.day_detail;
+    let day = detail_model.day;
+
+    let title = match model.registry.title(day) {
+        Some(title) => format!("Day {day}: {title}"),
+        None => format!("Day {day}"),
+    };
+    let block = Block::default()
+        .title(block::Title::from(title).alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().dim());
+
+    let mut lines = vec![match &detail_model.input_hash {
+        Some(hash) => Line::from(vec![
+            "Input  ".bold(),
+            format!("day{:02}.txt ", day.get()).into(),
+            format!("({hash})").fg(Color::DarkGray),
+        ]),
+        None => Line::from(vec![
+            "Input  ".bold(),
+            "missing ".fg(Color::Red),
+            "(press f to fetch it)".fg(Color::DarkGray),
+        ]),
+    }];
+    lines.push(Line::default());
+
+    let buttons: Vec<Span> = Part::ALL
+        .iter()
+        .flat_map(|&part| {
+            let label = format!(" Run part {part} ");
+            let button = if part == detail_model.selected_part {
+                label.bold().reversed()
+            } else {
+                label.fg(Color::Gray)
+            };
+            [button, "  ".into()]
+        })
+        .collect();
+    lines.push(Line::from(buttons));
+    lines.push(Line::default());
+
+    for part in Part::ALL {
+        let heading = format!("Part {part}  ").bold();
+        let star = if model.solved.contains(&(day, part)) {
+            " *".bold().fg(Color::Yellow)
+        } else {
+            "".into()
+        };
+        lines.push(match detail_model.results.get(&(day, part)) {
+            None => Line::from(vec![heading, "not run yet".fg(Color::DarkGray)]),
+            Some(PartResult::Running(None)) => {
+                Line::from(vec![heading, "running...".fg(Color::Cyan)])
+            },
+            Some(PartResult::Running(Some(progress))) => Line::from(vec![
+                heading,
+                format!("running... {:.0}%", progress * 100.0).fg(Color::Cyan),
+            ]),
+            Some(PartResult::Done {
+                output, examples, ..
+            }) => Line::from(vec![
+                heading,
+                output.answer.compact().bold().fg(Color::Green),
+                star,
+                format!(
+                    "  ({examples} examples passed, parsed in {:?}, solved in {:?})",
+                    output.parse_time, output.solve_time
+                )
+                .fg(Color::DarkGray),
+            ]),
+            Some(PartResult::ExampleFailed(failure)) => {
+                Line::from(vec![heading, failure.to_string().fg(Color::Red)])
+            },
+            Some(PartResult::Failed(_)) => Line::from(vec![
+                heading,
+                "failed".fg(Color::Red),
+                "  (see the error below)".fg(Color::DarkGray),
+            ]),
+            Some(PartResult::Cancelled) => Line::from(vec![heading, "cancelled".fg(Color::Yellow)]),
+        });
+        if let Some(PartResult::Done { output, notes, .. }) = detail_model.results.get(&(day, part))
+        {
+            if let Answer::Art(art) = &output.answer {
+                lines.extend(
+                    art.lines()
+                        .map(|line| Line::from(format!("        {line}").fg(Color::Green))),
+                );
+            }
+            let cycles = notes.cycles.iter().map(|cycle| format!("Cycle {cycle}"));
+            let memos = notes.memos.iter().map(|stats| format!("Memo {stats}"));
+            lines.extend(
+                cycles
+                    .chain(memos)
+                    .map(|note| Line::from(format!("        {note}").fg(Color::DarkGray))),
+            );
+        }
+    }
+
+    // Show the selected part's error if it failed, or else the other part's.
+    let failure = Part::ALL
+        .into_iter()
+        .filter_map(|part| match detail_model.results.get(&(day, part)) {
+            Some(PartResult::Failed(diagnostic)) => Some((part, diagnostic)),
+            _ => None,
+        })
+        .max_by_key(|&(part, _)| part == detail_model.selected_part);
+    let area = match failure {
+        Some((part, diagnostic)) => {
+            let error_lines = diagnostic.lines();
+            let height = error_lines.len() as u16 + 2;
+            let [area, error_area] = *Layout::new(
+                Direction::Vertical,
+                [Constraint::Min(0), Constraint::Length(height)],
+            )
+            .split(area) else {
+                return;
+            };
+            let error_block = Block::default()
+                .title(format!("Part {part} error"))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::new().fg(Color::Red));
+            f.render_widget(
+                Paragraph::new(error_lines)
+                    .wrap(Wrap { trim: false })
+                    .block(error_block),
+                error_area,
+            );
+            area
+        },
+        None => area,
+    };
+
+    f.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block),
+        area,
+    );
+}
+
+pub fn usage() -> Line<'static> {
+    Line::from(vec![
+        "enter/1/2".bold().fg(Color::Gray),
+        " to run, ".fg(Color::DarkGray),
+        "s".bold().fg(Color::Gray),
+        " to submit, ".fg(Color::DarkGray),
+        "c".bold().fg(Color::Gray),
+        " to cancel, ".fg(Color::DarkGray),
+        "f".bold().fg(Color::Gray),
+        " to fetch input, ".fg(Color::DarkGray),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aoc2023_common::{memo::MemoStats, Cycle, Parser};
+    use color_eyre::eyre::WrapErr;
+    use ratatui::backend::TestBackend;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn output(answer: &str) -> RunOutput {
+        RunOutput {
+            answer: answer.into(),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+        }
+    }
+
+    fn done(answer: &str) -> PartRun {
+        PartRun::Done {
+            output: output(answer),
+            examples: 1,
+            notes: notes(),
+        }
+    }
+
+    fn notes() -> RunNotes {
+        RunNotes {
+            cycles: vec![Cycle {
+                start: 2,
+                period: 5,
+            }],
+            memos: vec![MemoStats {
+                name: "arrangements",
+                hits: 3,
+                misses: 1,
+                entries: 1,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_run_unsolved_day() {
+        let (model, cmd) = update(Model::default(), DayDetailMessage::Run(Part::One));
+        assert!(model.day_detail.results.is_empty());
+        assert!(matches!(
+            cmd,
+            Cmd::Msg(Message::NotificationMessage(NotificationMessage::Show(
+                Notification::Error(_)
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_finished_records_result() {
+        let day = Day::new(4).unwrap();
+        let (model, _) = update(
+            Model::default(),
+            DayDetailMessage::Event {
+                day,
+                part: Part::Two,
+                event: RunEvent::Finished(Ok(done("13"))),
+            },
+        );
+        assert_eq!(
+            model.day_detail.results.get(&(day, Part::Two)),
+            Some(&PartResult::Done {
+                output: output("13"),
+                examples: 1,
+                notes: notes(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_cancel_running_parts() {
+        let mut model = Model::default();
+        let cancel = model.shutdown.child_token();
+        model
+            .day_detail
+            .running
+            .insert((Day::FIRST, Part::One), cancel.clone());
+
+        let (model, _) = update(model, DayDetailMessage::Cancel);
+        assert!(cancel.is_cancelled());
+
+        let (model, _) = update(
+            model,
+            DayDetailMessage::Event {
+                day: Day::FIRST,
+                part: Part::One,
+                event: RunEvent::Cancelled,
+            },
+        );
+        assert!(model.day_detail.running.is_empty());
+        assert_eq!(
+            model.day_detail.results.get(&(Day::FIRST, Part::One)),
+            Some(&PartResult::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_submit_opens_dialog() {
+        let (model, cmd) = update(Model::default(), DayDetailMessage::Submit(Part::One));
+        assert!(matches!(cmd, Cmd::Msg(Message::NotificationMessage(_))));
+
+        let (model, _) = update(
+            model,
+            DayDetailMessage::Event {
+                day: Day::FIRST,
+                part: Part::One,
+                event: RunEvent::Finished(Ok(done("142"))),
+            },
+        );
+        let (_, cmd) = update(model, DayDetailMessage::Submit(Part::One));
+        let Cmd::Msg(msg) = cmd else {
+            panic!("submitting should open the dialog");
+        };
+        assert_eq!(
+            msg,
+            Message::SubmitDialogMessage(SubmitDialogMessage::Open {
+                day: Day::FIRST,
+                part: Part::One,
+                answer: "142".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_submit_reads_art() {
+        let (model, _) = update(
+            Model::default(),
+            DayDetailMessage::Event {
+                day: Day::FIRST,
+                part: Part::One,
+                event: RunEvent::Finished(Ok(done(
+                    "#..#.###\n#..#..#.\n####..#.\n#..#..#.\n#..#..#.\n#..#.###",
+                ))),
+            },
+        );
+        let (_, cmd) = update(model, DayDetailMessage::Submit(Part::One));
+        let Cmd::Msg(Message::SubmitDialogMessage(SubmitDialogMessage::Open { answer, .. })) = cmd
+        else {
+            panic!("submitting should open the dialog");
+        };
+        assert_eq!(answer, "HI");
+    }
+
+    #[test]
+    fn test_failed_example_blocks_submit() {
+        let failure = ExampleOutcome {
+            day: Day::FIRST,
+            part: Part::One,
+            index: 1,
+            name: None,
+            expected: "142".into(),
+            actual: Ok("141".into()),
+        };
+        let (model, _) = update(
+            Model::default(),
+            DayDetailMessage::Event {
+                day: Day::FIRST,
+                part: Part::One,
+                event: RunEvent::Finished(Ok(PartRun::ExampleFailed(failure))),
+            },
+        );
+        let (_, cmd) = update(model, DayDetailMessage::Submit(Part::One));
+        assert!(matches!(
+            cmd,
+            Cmd::Msg(Message::NotificationMessage(NotificationMessage::Show(
+                Notification::Error(_)
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_failure_shown_in_error_pane() {
+        let error = Parser::parse_all("1 2 x", |p| p.list(Parser::unsigned::<u32>))
+            .wrap_err("Error parsing puzzle input")
+            .unwrap_err();
+        let (mut model, _) = update(
+            Model::default(),
+            DayDetailMessage::Event {
+                day: Day::FIRST,
+                part: Part::Two,
+                event: RunEvent::Finished(Err(Diagnostic::from_report(&error))),
+            },
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| view(&mut model, f, f.size())).unwrap();
+        let screen: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content
+            .chunks(80)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect();
+        let caret_row = screen
+            .iter()
+            .position(|row| row.contains("1 | 1 2 x"))
+            .unwrap();
+        assert_eq!(screen[caret_row + 1].find('^'), screen[caret_row].find('x'));
+        assert!(screen.iter().any(|row| row.contains("Part 2 error")));
+        assert!(screen.iter().any(|row| row.contains("Part 2  failed")));
+    }
+}
@@ -0,0 +1,207 @@
+use aoc2023_common::{Day, Part};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    command::Cmd,
+    message::Message,
+    model::Model,
+    router::{Navigation, Route},
+    tui::Frame,
+};
+
+/// Number of days in each row of the calendar.
+const COLUMNS: u8 = 5;
+
+/// A calendar of every day, showing which have been solved.
+#[derive(Debug)]
+pub struct DayPickerModel {
+    pub selected: Day,
+}
+
+impl Default for DayPickerModel {
+    fn default() -> Self {
+        Self {
+            selected: Day::FIRST,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DayPickerMessage {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+pub fn handle_key(model: &Model, key: KeyEvent) -> Option<Message> {
+    let msg = match key.code {
+        KeyCode::Up | KeyCode::Char('k') => DayPickerMessage::Up,
+        KeyCode::Down | KeyCode::Char('j') => DayPickerMessage::Down,
+        KeyCode::Left | KeyCode::Char('h') => DayPickerMessage::Left,
+        KeyCode::Right | KeyCode::Char('l') => DayPickerMessage::Right,
+        KeyCode::Enter => {
+            let route = Route::DayDetail(model.day_picker.selected);
+            return Some(Message::Navigate(Navigation::Push(route)));
+        },
+        KeyCode::Char('b') => {
+            return Some(Message::Navigate(Navigation::Push(Route::BenchResults)));
+        },
+        _ => return None,
+    };
+    Some(Message::DayPickerMessage(msg))
+}
+
+pub fn update(mut model: Model, msg: DayPickerMessage) -> (Model, Cmd<Message>) {
+    let picker_model = &mut model.day_picker;
+
+    // Work with zero-based rows and columns, and ignore any moves off the grid.
+    let index = picker_model.selected.get() - 1;
+    let (row, col) = (index / COLUMNS, index % COLUMNS);
+    let (row, col) = match msg {
+        DayPickerMessage::Up => (row.saturating_sub(1), col),
+        DayPickerMessage::Down => (row + 1, col),
+        DayPickerMessage::Left => (row, col.saturating_sub(1)),
+        DayPickerMessage::Right => (row, (col + 1).min(COLUMNS - 1)),
+    };
+    if let Some(day) = Day::new(row * COLUMNS + col + 1) {
+        picker_model.selected = day;
+    }
+
+    (model, Cmd::None)
+}
+
+pub fn view(model: &mut Model, f: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(block::Title::from("Advent of Code 2023").alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().dim());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::new(
+        Direction::Vertical,
+        [Constraint::Ratio(1, COLUMNS as u32); COLUMNS as usize],
+    )
+    .split(inner);
+
+    for (row, row_area) in Day::all()
+        .collect::<Vec<_>>()
+        .chunks(COLUMNS as usize)
+        .zip(rows.iter())
+    {
+        let cells = Layout::new(
+            Direction::Horizontal,
+            [Constraint::Ratio(1, COLUMNS as u32); COLUMNS as usize],
+        )
+        .split(*row_area);
+
+        for (&day, &cell) in row.iter().zip(cells.iter()) {
+            render_day(model, f, day, cell);
+        }
+    }
+}
+
+/// Render a single day's cell in the calendar.
+fn render_day(model: &Model, f: &mut Frame, day: Day, area: Rect) {
+    let registered = model.registry.contains(day);
+    let selected = model.day_picker.selected == day;
+
+    let border_style = match (selected, registered) {
+        (true, _) => Style::new().fg(Color::Yellow),
+        (false, true) => Style::new().fg(Color::Gray),
+        (false, false) => Style::new().fg(Color::DarkGray),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(if selected {
+            BorderType::Thick
+        } else {
+            BorderType::Rounded
+        })
+        .border_style(border_style);
+
+    let stars: Vec<Span> = Part::ALL
+        .iter()
+        .map(|&part| {
+            if model.solved.contains(&(day, part)) {
+                "*".bold().fg(Color::Yellow)
+            } else {
+                "*".fg(Color::DarkGray)
+            }
+        })
+        .collect();
+
+    let title = if registered {
+        format!("Day {day}").bold()
+    } else {
+        format!("Day {day}").fg(Color::DarkGray)
+    };
+
+    f.render_widget(
+        Paragraph::new(vec![Line::from(title), Line::from(stars)])
+            .alignment(Alignment::Center)
+            .block(block),
+        area,
+    );
+}
+
+pub fn usage() -> Line<'static> {
+    Line::from(vec![
+        "arrows/hjkl".bold().fg(Color::Gray),
+        " to move, ".fg(Color::DarkGray),
+        "enter".bold().fg(Color::Gray),
+        " to open, ".fg(Color::DarkGray),
+        "b".bold().fg(Color::Gray),
+        " for benchmarks, ".fg(Color::DarkGray),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn model_at(day: u8) -> Model {
+        let mut model = Model::default();
+        model.day_picker.selected = Day::new(day).unwrap();
+        model
+    }
+
+    fn moved(day: u8, msg: DayPickerMessage) -> u8 {
+        let (model, cmd) = update(model_at(day), msg);
+        assert!(matches!(cmd, Cmd::None));
+        model.day_picker.selected.get()
+    }
+
+    #[test]
+    fn test_move_within_grid() {
+        assert_eq!(moved(7, DayPickerMessage::Up), 2);
+        assert_eq!(moved(7, DayPickerMessage::Down), 12);
+        assert_eq!(moved(7, DayPickerMessage::Left), 6);
+        assert_eq!(moved(7, DayPickerMessage::Right), 8);
+    }
+
+    #[test]
+    fn test_move_clamps_at_edges() {
+        assert_eq!(moved(3, DayPickerMessage::Up), 3);
+        assert_eq!(moved(23, DayPickerMessage::Down), 23);
+        assert_eq!(moved(11, DayPickerMessage::Left), 11);
+        assert_eq!(moved(15, DayPickerMessage::Right), 15);
+    }
+
+    #[test]
+    fn test_enter_opens_selected_day() {
+        let model = model_at(9);
+        let msg = handle_key(&model, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(
+            msg,
+            Some(Message::Navigate(Navigation::Push(Route::DayDetail(
+                Day::new(9).unwrap()
+            ))))
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Errors from failed runs, laid out to be read by a person.
+//!
+//! A [`Diagnostic`] keeps the eyre context chain of an error, along with the
+//! [`ParseError`] at the bottom of it, if there is one, so the offending line
+//! can be shown with a caret under the problem. The same lines are drawn in
+//! the TUI's error pane and printed to stderr by headless commands.
+
+use std::{
+    fmt,
+    io::{self, IsTerminal},
+};
+
+use aoc2023_common::ParseError;
+use color_eyre::eyre::Report;
+use crossterm::style::{Attribute, ContentStyle};
+use ratatui::prelude::*;
+
+/// An error, ready to be shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Each error in the chain above the parse error, outermost first.
+    pub context: Vec<String>,
+    /// The error that caused everything else, if it was a parse error.
+    pub parse_error: Option<ParseError>,
+}
+
+impl Diagnostic {
+    pub fn from_report(report: &Report) -> Self {
+        let mut context = Vec::new();
+        for error in report.chain() {
+            if let Some(parse_error) = error.downcast_ref::<ParseError>() {
+                return Self {
+                    context,
+                    parse_error: Some(parse_error.clone()),
+                };
+            }
+            context.push(error.to_string());
+        }
+        Self {
+            context,
+            parse_error: None,
+        }
+    }
+
+    /// Every message in the chain, outermost first.
+    fn messages(&self) -> impl Iterator<Item = String> + '_ {
+        let parse_message = self.parse_error.as_ref().map(ParseError::message);
+        self.context.iter().cloned().chain(parse_message)
+    }
+
+    /// The whole chain on one line, like `{:#}` does for an eyre report.
+    pub fn summary(&self) -> String {
+        self.messages().collect::<Vec<_>>().join(": ")
+    }
+
+    /// The diagnostic as styled lines: the outermost error, then its causes,
+    /// then the line that couldn't be parsed.
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        let mut messages = self.messages();
+        let mut lines: Vec<Line> = messages
+            .next()
+            .map(|message| Line::from(message.bold().fg(Color::Red)))
+            .into_iter()
+            .collect();
+        lines.extend(
+            messages.map(|message| {
+                Line::from(vec!["  Caused by: ".fg(Color::DarkGray), message.into()])
+            }),
+        );
+
+        if let Some(e) = &self.parse_error {
+            let number = e.line.to_string();
+            let gutter = " ".repeat(number.len());
+            lines.extend([
+                Line::from(format!("  {gutter} |").fg(Color::Blue)),
+                Line::from(vec![
+                    format!("  {number} | ").fg(Color::Blue),
+                    e.line_text.clone().into(),
+                ]),
+                Line::from(vec![
+                    format!("  {gutter} | ").fg(Color::Blue),
+                    e.caret_indent().into(),
+                    "^".bold().fg(Color::Red),
+                ]),
+            ]);
+        }
+        lines
+    }
+
+    /// The diagnostic with ANSI colour codes, for printing to a terminal.
+    pub fn to_ansi(&self) -> String {
+        let lines: Vec<String> = self
+            .lines()
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| {
+                        let mut style = ContentStyle::new();
+                        style.foreground_color = span.style.fg.map(Into::into);
+                        if span.style.add_modifier.contains(Modifier::BOLD) {
+                            style.attributes.set(Attribute::Bold);
+                        }
+                        style.apply(span.content.as_ref()).to_string()
+                    })
+                    .collect()
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    /// Print the diagnostic to stderr, in colour if it's a terminal and
+    /// `NO_COLOR` isn't set.
+    pub fn eprint(&self) {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        if io::stderr().is_terminal() && !no_color {
+            eprintln!("{}", self.to_ansi());
+        } else {
+            eprintln!("{self}");
+        }
+    }
+}
+
+impl From<String> for Diagnostic {
+    fn from(message: String) -> Self {
+        Self {
+            context: vec![message],
+            parse_error: None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self
+            .lines()
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2023_common::Parser;
+    use color_eyre::eyre::{Result, WrapErr};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse_report() -> Report {
+        let result: Result<u32> = Parser::parse_all("Game 1: 3 blue\nGame x: 2 red", |p| {
+            p.lines(|p| {
+                p.keyword("Game ")?;
+                let id = p.unsigned()?;
+                p.take_while(|c| c != '\n');
+                Ok(id)
+            })
+        })
+        .map(|ids| ids.iter().sum())
+        .wrap_err("Error parsing puzzle input")
+        .wrap_err("Error running day 2 part 1");
+        result.unwrap_err()
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let diagnostic = Diagnostic::from_report(&parse_report());
+        assert_eq!(
+            diagnostic.context,
+            vec!["Error running day 2 part 1", "Error parsing puzzle input"]
+        );
+        assert_eq!(diagnostic.parse_error.as_ref().map(|e| e.column), Some(6));
+        assert_eq!(
+            diagnostic.to_string(),
+            "\
+Error running day 2 part 1
+  Caused by: Error parsing puzzle input
+  Caused by: Expected an integer at line 2, column 6, but found 'x'
+    |
+  2 | Game x: 2 red
+    |      ^"
+        );
+        assert_eq!(
+            diagnostic.summary(),
+            "Error running day 2 part 1: Error parsing puzzle input: Expected an integer at line 2, column 6, but found 'x'"
+        );
+    }
+
+    #[test]
+    fn test_other_error() {
+        let report = Report::msg("File not found").wrap_err("Error loading input");
+        let diagnostic = Diagnostic::from_report(&report);
+        assert_eq!(diagnostic.parse_error, None);
+        assert_eq!(
+            diagnostic.to_string(),
+            "Error loading input\n  Caused by: File not found"
+        );
+        assert_eq!(Diagnostic::from("Oh no".to_string()).to_string(), "Oh no");
+    }
+
+    #[test]
+    fn test_ansi() {
+        let ansi = Diagnostic::from_report(&parse_report()).to_ansi();
+        assert!(ansi.contains("\u{1b}["));
+        assert_eq!(
+            strip_ansi_escapes::strip_str(&ansi),
+            Diagnostic::from_report(&parse_report()).to_string()
+        );
+    }
+}
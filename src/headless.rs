@@ -0,0 +1,443 @@
+//! Runs [`Command`]s without the TUI, printing results to stdout.
+
+use std::{io::Write, path::Path};
+
+use aoc2023_common::{BenchOptions, Day, InputStore, Part, PuzzleInput, Registry};
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{
+    answers::KnownAnswers,
+    bench::{bench_all, change, BenchStore},
+    cli::Command,
+    client::AocClient,
+    ledger::Ledger,
+    solutions,
+    utils::{get_data_dir, GIT_COMMIT_HASH},
+};
+
+/// Run a command, printing its results to stdout.
+pub async fn run(command: Command) -> Result<()> {
+    let registry = solutions::registry();
+    let inputs = InputStore::new(get_data_dir());
+    let mut out = std::io::stdout().lock();
+
+    match command {
+        Command::Run { day, part, input } => {
+            run_day(&registry, &inputs, day, part, input.as_deref(), &mut out)
+        },
+        Command::List => list(
+            &registry,
+            &inputs,
+            &Ledger::load(Ledger::default_path())?,
+            &mut out,
+        ),
+        Command::Bench { day, runs, warmup } => bench(
+            &registry,
+            &inputs,
+            &BenchStore::default(),
+            day,
+            BenchOptions { warmup, runs },
+            &mut out,
+        ),
+        Command::Verify { day } => verify(
+            &registry,
+            &inputs,
+            &KnownAnswers::load(KnownAnswers::default_path())?,
+            day,
+            &mut out,
+        ),
+        Command::Fetch { day } => fetch(&registry, &AocClient::default(), day, &mut out).await,
+    }
+}
+
+/// The parts to run, given an optional `--part` argument.
+fn parts(part: Option<Part>) -> Vec<Part> {
+    part.map_or_else(|| Part::ALL.to_vec(), |part| vec![part])
+}
+
+/// Run one or both parts of a day, and print the answers.
+fn run_day(
+    registry: &Registry,
+    inputs: &InputStore,
+    day: Day,
+    part: Option<Part>,
+    input_path: Option<&Path>,
+    out: &mut impl Write,
+) -> Result<()> {
+    let title = registry
+        .title(day)
+        .ok_or_else(|| eyre!("Day {day} hasn't been solved yet"))?;
+    let input = match input_path {
+        Some(path) => PuzzleInput::from_file(path)?,
+        None => inputs.load(day)?,
+    };
+
+    writeln!(out, "Day {day}: {title}")?;
+    for part in parts(part) {
+        let output = registry.get(day, part).unwrap().run(&input.text)?;
+        writeln!(
+            out,
+            "  Part {part}: {}  (parsed in {:?}, solved in {:?})",
+            output.answer, output.parse_time, output.solve_time
+        )?;
+    }
+
+    Ok(())
+}
+
+/// List every registered day, along with its input and star status.
+fn list(
+    registry: &Registry,
+    inputs: &InputStore,
+    ledger: &Ledger,
+    out: &mut impl Write,
+) -> Result<()> {
+    if registry.is_empty() {
+        writeln!(out, "No days have been solved yet.")?;
+        return Ok(());
+    }
+
+    for day in registry.days() {
+        let stars: String = Part::ALL
+            .iter()
+            .map(|&part| match ledger.solution(day, part) {
+                Some(_) => '*',
+                None => '.',
+            })
+            .collect();
+        let input = if inputs.contains(day) {
+            "input"
+        } else {
+            "no input"
+        };
+        writeln!(
+            out,
+            "Day {day:>2}  {stars}  {input:<8}  {}",
+            registry.title(day).unwrap()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Benchmark every registered part, print the timings, and save them for this commit.
+///
+/// Each part's median solve time is compared against the most recent benchmark
+/// of a different commit, if there is one.
+fn bench(
+    registry: &Registry,
+    inputs: &InputStore,
+    store: &BenchStore,
+    day: Option<Day>,
+    options: BenchOptions,
+    out: &mut impl Write,
+) -> Result<()> {
+    let previous = store.previous(GIT_COMMIT_HASH)?;
+    let report = bench_all(registry, inputs, store, GIT_COMMIT_HASH, day, options)?;
+
+    writeln!(
+        out,
+        "Day Part  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "parse", "min", "median", "mean", "p95"
+    )?;
+    for result in report
+        .results
+        .iter()
+        .filter(|result| day.is_none_or(|day| result.day == day))
+    {
+        write!(
+            out,
+            "{:>3} {:>4}  {:>10?}  {:>10?}  {:>10?}  {:>10?}  {:>10?}",
+            result.day,
+            result.part,
+            result.parse.median,
+            result.solve.min,
+            result.solve.median,
+            result.solve.mean,
+            result.solve.p95,
+        )?;
+        if let Some((change, previous)) = previous.as_ref().and_then(|previous| {
+            let old = previous.get(result.day, result.part)?;
+            Some((change(old.solve.median, result.solve.median)?, previous))
+        }) {
+            write!(out, "  {change:+.1}% vs {}", previous.commit)?;
+        }
+        writeln!(out)?;
+    }
+    for skipped in registry
+        .days()
+        .filter(|&d| day.is_none_or(|day| d == day) && !inputs.contains(d))
+    {
+        writeln!(out, "Skipped day {skipped}, as it has no puzzle input")?;
+    }
+    writeln!(out, "Saved to {}", store.path(GIT_COMMIT_HASH).display())?;
+
+    Ok(())
+}
+
+/// Re-run every part with a known answer, printing a diff for any that changed.
+///
+/// Fails if any answer changed, if a part with a known answer couldn't be run or
+/// isn't solved any more, or if there were no answers to check.
+fn verify(
+    registry: &Registry,
+    inputs: &InputStore,
+    answers: &KnownAnswers,
+    day: Option<Day>,
+    out: &mut impl Write,
+) -> Result<()> {
+    let (mut checked, mut failed) = (0, 0);
+    for (day, part, expected) in answers
+        .iter()
+        .filter(|&(d, _, _)| day.is_none_or(|day| d == day))
+    {
+        checked += 1;
+        let Some(solution) = registry.get(day, part) else {
+            failed += 1;
+            writeln!(
+                out,
+                "Day {day:>2} part {part}  MISSING  no solution is registered"
+            )?;
+            continue;
+        };
+
+        let result = inputs.load(day).and_then(|input| solution.run(&input.text));
+        match result {
+            Ok(output) if output.answer.matches(expected) => {
+                writeln!(out, "Day {day:>2} part {part}  ok")?;
+            },
+            Ok(output) => {
+                failed += 1;
+                writeln!(out, "Day {day:>2} part {part}  CHANGED")?;
+                for line in expected.lines() {
+                    writeln!(out, "  - {line}")?;
+                }
+                for line in output.answer.to_string().lines() {
+                    writeln!(out, "  + {line}")?;
+                }
+            },
+            Err(e) => {
+                failed += 1;
+                writeln!(out, "Day {day:>2} part {part}  FAILED  {e:#}")?;
+            },
+        }
+    }
+
+    if failed > 0 {
+        return Err(eyre!("{failed} of {checked} answers could not be verified"));
+    }
+    if checked == 0 {
+        return Err(eyre!("There are no known answers to verify"));
+    }
+    writeln!(out, "Verified {checked} answers.")?;
+    Ok(())
+}
+
+/// Download the input for `day`, or for every registered day whose input is missing.
+async fn fetch(
+    registry: &Registry,
+    client: &AocClient,
+    day: Option<Day>,
+    out: &mut impl Write,
+) -> Result<()> {
+    let days: Vec<Day> = match day {
+        Some(day) => vec![day],
+        None => registry
+            .days()
+            .filter(|&day| !client.inputs().contains(day))
+            .collect(),
+    };
+
+    if days.is_empty() {
+        writeln!(out, "Every input has already been downloaded.")?;
+    }
+    for day in days {
+        let input = client.fetch_input(day).await?;
+        writeln!(
+            out,
+            "Day {day:>2}  {}  {}",
+            input.short_hash(),
+            input.path.display()
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2023_common::Solution;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Counts the lines and characters in the input.
+    struct Count;
+
+    impl Solution for Count {
+        type Answer1 = usize;
+        type Answer2 = usize;
+        type Input = String;
+
+        const TITLE: &'static str = "Counting";
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            Ok(input.to_string())
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+            Ok(input.lines().count())
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+            Ok(input.chars().count())
+        }
+    }
+
+    fn setup() -> (Registry, InputStore, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Registry::new().register(Day::new(2).unwrap(), Count);
+        let inputs = InputStore::new(dir.path());
+        (registry, inputs, dir)
+    }
+
+    #[test]
+    fn test_run_day() {
+        let (registry, inputs, _dir) = setup();
+        let day = Day::new(2).unwrap();
+        inputs.save(day, "ab\r\ncd\r\n").unwrap();
+
+        let mut out = Vec::new();
+        run_day(&registry, &inputs, day, Some(Part::One), None, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "Day 2: Counting");
+        assert!(lines[1].starts_with("  Part 1: 2  "), "{out}");
+    }
+
+    #[test]
+    fn test_run_day_with_input_file() {
+        let (registry, inputs, dir) = setup();
+        let path = dir.path().join("custom.txt");
+        std::fs::write(&path, "abc\n").unwrap();
+
+        let mut out = Vec::new();
+        run_day(
+            &registry,
+            &inputs,
+            Day::new(2).unwrap(),
+            None,
+            Some(&path),
+            &mut out,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("  Part 1: 1  "), "{out}");
+        assert!(out.contains("  Part 2: 3  "), "{out}");
+    }
+
+    #[test]
+    fn test_run_unsolved_day() {
+        let (registry, inputs, _dir) = setup();
+        let err = run_day(
+            &registry,
+            &inputs,
+            Day::new(3).unwrap(),
+            None,
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Day 3 hasn't been solved yet");
+    }
+
+    #[test]
+    fn test_list() {
+        let (registry, inputs, dir) = setup();
+        let ledger = Ledger::load(dir.path().join("ledger.json")).unwrap();
+
+        let mut out = Vec::new();
+        list(&registry, &inputs, &ledger, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Day  2  ..  no input  Counting\n"
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        let (registry, inputs, dir) = setup();
+        inputs.save(Day::new(2).unwrap(), "abc\n").unwrap();
+        let store = BenchStore::new(dir.path());
+        let options = BenchOptions { warmup: 0, runs: 3 };
+
+        let mut out = Vec::new();
+        bench(&registry, &inputs, &store, None, options, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 4, "{out}");
+        assert!(lines[1].starts_with("  2    1  "), "{out}");
+        assert!(lines[2].starts_with("  2    2  "), "{out}");
+        assert_eq!(store.load(GIT_COMMIT_HASH).unwrap().results.len(), 2);
+    }
+
+    #[test]
+    fn test_bench_skips_days_without_input() {
+        let (registry, inputs, dir) = setup();
+        let registry = registry.register(Day::new(5).unwrap(), Count);
+        inputs.save(Day::new(2).unwrap(), "abc\n").unwrap();
+        let store = BenchStore::new(dir.path());
+        let options = BenchOptions { warmup: 0, runs: 1 };
+
+        let mut out = Vec::new();
+        bench(&registry, &inputs, &store, None, options, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 5, "{out}");
+        assert_eq!(lines[3], "Skipped day 5, as it has no puzzle input");
+        assert_eq!(store.load(GIT_COMMIT_HASH).unwrap().results.len(), 2);
+    }
+
+    #[test]
+    fn test_verify() {
+        let (registry, inputs, dir) = setup();
+        let day = Day::new(2).unwrap();
+        inputs.save(day, "ab\ncd\n").unwrap();
+        let mut answers = KnownAnswers::load(dir.path().join("answers.toml")).unwrap();
+        let mut out = Vec::new();
+        let err = verify(&registry, &inputs, &answers, None, &mut out).unwrap_err();
+        assert_eq!(err.to_string(), "There are no known answers to verify");
+
+        answers.record(day, Part::One, "2").unwrap();
+        let mut out = Vec::new();
+        verify(&registry, &inputs, &answers, None, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Day  2 part 1  ok\nVerified 1 answers.\n"
+        );
+
+        // A known answer whose solution has gone missing is a failure.
+        answers
+            .record(Day::new(3).unwrap(), Part::One, "9")
+            .unwrap();
+        let mut out = Vec::new();
+        let err = verify(&registry, &inputs, &answers, None, &mut out).unwrap_err();
+        assert_eq!(err.to_string(), "1 of 2 answers could not be verified");
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Day  2 part 1  ok\nDay  3 part 1  MISSING  no solution is registered\n"
+        );
+
+        answers.record(day, Part::Two, "4").unwrap();
+        let mut out = Vec::new();
+        assert!(verify(&registry, &inputs, &answers, Some(day), &mut out).is_err());
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Day  2 part 1  ok\nDay  2 part 2  CHANGED\n  - 4\n  + 5\n"
+        );
+    }
+}
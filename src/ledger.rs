@@ -0,0 +1,307 @@
+//! A local record of every answer submitted to the Advent of Code website.
+//!
+//! The ledger is stored as JSON in the data directory, and is used to avoid
+//! submitting the same wrong answer twice.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use aoc2023_common::{Day, Part};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::get_data_dir;
+
+/// The name of the ledger file inside the data directory.
+pub const LEDGER_FILE: &str = "ledger.json";
+
+/// What the website said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmitOutcome {
+    /// The answer was right.
+    Correct,
+    /// The answer was wrong.
+    Incorrect,
+    /// The answer was wrong, and too high.
+    TooHigh,
+    /// The answer was wrong, and too low.
+    TooLow,
+    /// An answer was submitted too recently, so this one wasn't checked.
+    Wait { seconds: Option<u64> },
+    /// The answer wasn't checked, because this part has already been solved,
+    /// or because it isn't unlocked yet.
+    AlreadySolved,
+}
+
+impl SubmitOutcome {
+    /// Parse the HTML page returned by the website after submitting an answer.
+    pub fn parse(html: &str) -> Result<Self> {
+        if html.contains("That's the right answer") {
+            Ok(Self::Correct)
+        } else if html.contains("your answer is too high") {
+            Ok(Self::TooHigh)
+        } else if html.contains("your answer is too low") {
+            Ok(Self::TooLow)
+        } else if html.contains("That's not the right answer") {
+            Ok(Self::Incorrect)
+        } else if html.contains("You gave an answer too recently") {
+            Ok(Self::Wait {
+                seconds: parse_wait_time(html),
+            })
+        } else if html.contains("You don't seem to be solving the right level") {
+            Ok(Self::AlreadySolved)
+        } else {
+            Err(eyre!("Unrecognised response to answer submission"))
+        }
+    }
+
+    /// Whether this outcome says anything about the submitted answer.
+    ///
+    /// Answers with conclusive outcomes are never submitted again.
+    pub fn is_conclusive(&self) -> bool {
+        matches!(
+            self,
+            Self::Correct | Self::Incorrect | Self::TooHigh | Self::TooLow
+        )
+    }
+
+    /// Whether this outcome proves the answer is right, and so the part is
+    /// solved.
+    pub fn is_solved(&self) -> bool {
+        matches!(self, Self::Correct)
+    }
+}
+
+impl fmt::Display for SubmitOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "That's the right answer!"),
+            Self::Incorrect => write!(f, "That's not the right answer."),
+            Self::TooHigh => write!(f, "That's not the right answer; it's too high."),
+            Self::TooLow => write!(f, "That's not the right answer; it's too low."),
+            Self::Wait { seconds: Some(s) } => {
+                write!(f, "Answered too recently; wait {s}s before trying again.")
+            },
+            Self::Wait { seconds: None } => {
+                write!(f, "Answered too recently; wait a bit before trying again.")
+            },
+            Self::AlreadySolved => write!(
+                f,
+                "The answer wasn't checked; this part is already solved, or isn't unlocked yet."
+            ),
+        }
+    }
+}
+
+/// Parse the "You have 1m 23s left to wait" message into a number of seconds.
+fn parse_wait_time(html: &str) -> Option<u64> {
+    let end = html.find(" left to wait")?;
+    let start = html[..end].rfind("You have ")? + "You have ".len();
+
+    html[start..end]
+        .split_whitespace()
+        .try_fold(0, |total, unit| {
+            let (n, multiplier) = if let Some(n) = unit.strip_suffix('h') {
+                (n, 3600)
+            } else if let Some(n) = unit.strip_suffix('m') {
+                (n, 60)
+            } else {
+                (unit.strip_suffix('s')?, 1)
+            };
+            Some(total + n.parse::<u64>().ok()? * multiplier)
+        })
+}
+
+/// A single submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub day: Day,
+    pub part: Part,
+    pub answer: String,
+    pub outcome: SubmitOutcome,
+    /// When the answer was submitted, in seconds since the Unix epoch.
+    pub submitted_at: u64,
+}
+
+impl Attempt {
+    /// Record an attempt that was just submitted.
+    pub fn now(day: Day, part: Part, answer: impl Into<String>, outcome: SubmitOutcome) -> Self {
+        Self {
+            day,
+            part,
+            answer: answer.into(),
+            outcome,
+            submitted_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO)
+                .as_secs(),
+        }
+    }
+}
+
+/// Every answer that has ever been submitted, backed by a JSON file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ledger {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    /// The default location of the ledger, inside the data directory.
+    pub fn default_path() -> PathBuf {
+        get_data_dir().join(LEDGER_FILE)
+    }
+
+    /// Load the ledger from `path`, starting a new one if it doesn't exist yet.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let attempts = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)
+                .wrap_err_with(|| format!("Error parsing answer ledger {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(e)
+                    .wrap_err_with(|| format!("Error reading answer ledger {}", path.display()))
+            },
+        };
+        Ok(Self { path, attempts })
+    }
+
+    /// The file this ledger is stored in.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every recorded attempt, oldest first.
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Find a conclusive attempt at submitting this exact answer.
+    pub fn previous(&self, day: Day, part: Part, answer: &str) -> Option<&Attempt> {
+        self.attempts.iter().find(|a| {
+            a.day == day && a.part == part && a.answer == answer && a.outcome.is_conclusive()
+        })
+    }
+
+    /// The accepted answer for a part, if it has been solved.
+    pub fn solution(&self, day: Day, part: Part) -> Option<&Attempt> {
+        self.attempts
+            .iter()
+            .find(|a| a.day == day && a.part == part && a.outcome.is_solved())
+    }
+
+    /// Add an attempt to the ledger, and save it to disk.
+    pub fn record(&mut self, attempt: Attempt) -> Result<()> {
+        self.attempts.push(attempt);
+        self.save()
+    }
+
+    /// Write the ledger to disk.
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .wrap_err_with(|| format!("Error creating directory {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(&self.attempts)?;
+        fs::write(&self.path, json)
+            .wrap_err_with(|| format!("Error writing answer ledger {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_outcome() {
+        let outcome = |s: &str| SubmitOutcome::parse(&format!("<article><p>{s}</p></article>"));
+
+        assert_eq!(
+            outcome("That's the right answer! You are one gold star closer.").unwrap(),
+            SubmitOutcome::Correct
+        );
+        assert_eq!(
+            outcome("That's not the right answer; your answer is too high.").unwrap(),
+            SubmitOutcome::TooHigh
+        );
+        assert_eq!(
+            outcome("That's not the right answer; your answer is too low.").unwrap(),
+            SubmitOutcome::TooLow
+        );
+        assert_eq!(
+            outcome("That's not the right answer. If you're stuck, ...").unwrap(),
+            SubmitOutcome::Incorrect
+        );
+        assert_eq!(
+            outcome("You gave an answer too recently. You have 1m 23s left to wait.").unwrap(),
+            SubmitOutcome::Wait { seconds: Some(83) }
+        );
+        assert_eq!(
+            outcome("You don't seem to be solving the right level.").unwrap(),
+            SubmitOutcome::AlreadySolved
+        );
+        assert!(outcome("Something else entirely").is_err());
+    }
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LEDGER_FILE);
+        let day = Day::new(9).unwrap();
+
+        let mut ledger = Ledger::load(&path).unwrap();
+        assert!(ledger.attempts().is_empty());
+
+        ledger
+            .record(Attempt::now(day, Part::One, "12", SubmitOutcome::TooLow))
+            .unwrap();
+        ledger
+            .record(Attempt::now(
+                day,
+                Part::One,
+                "13",
+                SubmitOutcome::Wait { seconds: None },
+            ))
+            .unwrap();
+
+        let ledger = Ledger::load(&path).unwrap();
+        assert_eq!(ledger.attempts().len(), 2);
+        assert!(ledger.previous(day, Part::One, "12").is_some());
+        assert!(ledger.previous(day, Part::One, "13").is_none());
+        assert!(ledger.previous(day, Part::Two, "12").is_none());
+        assert!(ledger.solution(day, Part::One).is_none());
+    }
+
+    #[test]
+    fn test_already_solved_is_not_a_solution() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ledger = Ledger::load(dir.path().join(LEDGER_FILE)).unwrap();
+        let day = Day::new(4).unwrap();
+
+        ledger
+            .record(Attempt::now(
+                day,
+                Part::Two,
+                "7",
+                SubmitOutcome::AlreadySolved,
+            ))
+            .unwrap();
+        // The website also says this when part 1 isn't solved yet, so the
+        // answer was never checked, and can be submitted again.
+        assert!(!SubmitOutcome::AlreadySolved.is_solved());
+        assert!(ledger.previous(day, Part::Two, "7").is_none());
+        assert!(ledger.solution(day, Part::Two).is_none());
+
+        ledger
+            .record(Attempt::now(day, Part::Two, "8", SubmitOutcome::Correct))
+            .unwrap();
+        assert_eq!(ledger.solution(day, Part::Two).unwrap().answer, "8");
+    }
+}
@@ -1,9 +1,23 @@
+pub mod answers;
 pub mod app;
+pub mod bench;
+pub mod bench_results;
 pub mod cli;
+pub mod client;
 pub mod command;
+pub mod day_detail;
+pub mod day_picker;
+pub mod diagnostic;
 pub mod fps_counter;
+pub mod headless;
+pub mod ledger;
 pub mod message;
 pub mod model;
+pub mod notification;
+pub mod router;
+pub mod runner;
+pub mod solutions;
+pub mod submit_dialog;
 pub mod subscriptions;
 pub mod termination;
 pub mod tui;
@@ -16,6 +30,7 @@ use color_eyre::eyre::Result;
 use crate::{
     app::App,
     cli::Cli,
+    diagnostic::Diagnostic,
     termination::create_termination,
     utils::{initialize_logging, initialize_panic_handler, version},
 };
@@ -38,9 +53,18 @@ async fn tokio_main() -> Result<()> {
     initialize_panic_handler()?;
     initialize_logging()?;
 
-    let (terminator, interrupt_rx) = create_termination();
-
     let args = Cli::parse();
+    if let Some(command) = args.command {
+        // Headless errors are shown the same way as in the TUI, rather than as an
+        // eyre report.
+        if let Err(e) = headless::run(command).await {
+            Diagnostic::from_report(&e).eprint();
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let (terminator, interrupt_rx) = create_termination();
     let mut app = App::new(
         args.tick_rate,
         args.frame_rate,
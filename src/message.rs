@@ -1,13 +1,28 @@
-use crate::fps_counter;
+use aoc2023_common::{Day, PuzzleInput};
+use crossterm::event::KeyEvent;
+
+use crate::{
+    bench_results, day_detail, day_picker, fps_counter, notification, router, submit_dialog,
+};
 
 #[derive(Debug, PartialEq)]
 pub enum Message {
-    Increment,
-    Decrement,
     Render,
     Tick,
     Resize(u16, u16),
-    Reset,
+    /// A key was pressed. What it does depends on what's on screen.
+    Key(KeyEvent),
     Quit,
+    /// Change which screen is being shown.
+    Navigate(router::Navigation),
     FpsCounterMessage(fps_counter::FpsCounterMessage),
+    NotificationMessage(notification::NotificationMessage),
+    SubmitDialogMessage(submit_dialog::SubmitDialogMessage),
+    DayPickerMessage(day_picker::DayPickerMessage),
+    DayDetailMessage(day_detail::DayDetailMessage),
+    BenchResultsMessage(bench_results::BenchResultsMessage),
+    /// Download a day's input, if it hasn't been already.
+    FetchInput(Day),
+    /// A day's input has been fetched, or failed to fetch.
+    InputFetched(Day, Result<PuzzleInput, String>),
 }
@@ -1,13 +1,41 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use aoc2023_common::{Day, Part, Registry};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::Rect;
+use tokio_util::sync::CancellationToken;
 
-use crate::{command::Cmd, fps_counter, message::Message, tui::Tui};
+use crate::{
+    bench::BenchStore,
+    bench_results,
+    client::AocClient,
+    command::Cmd,
+    day_detail, day_picker, fps_counter,
+    ledger::Ledger,
+    message::Message,
+    notification::{self, Notification, NotificationMessage},
+    router, solutions, submit_dialog,
+    tui::Tui,
+};
 
 #[derive(Debug, Default)]
 pub struct Model {
-    pub counter: i32,
     pub running_state: RunningState,
     pub tui_size: Rect,
+    pub router: router::Router,
     pub fps_counter: fps_counter::FpsCounterModel,
+    pub notification: notification::NotificationModel,
+    pub submit_dialog: submit_dialog::SubmitDialogModel,
+    pub day_picker: day_picker::DayPickerModel,
+    pub day_detail: day_detail::DayDetailModel,
+    pub bench_results: bench_results::BenchResultsModel,
+    pub client: AocClient,
+    pub bench_store: BenchStore,
+    pub registry: Arc<Registry>,
+    /// Every part that has been solved, according to the answer ledger.
+    pub solved: BTreeSet<(Day, Part)>,
+    /// Cancelled when the app shuts down, stopping any background work.
+    pub shutdown: CancellationToken,
 }
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -18,33 +46,57 @@ pub enum RunningState {
     ShouldSuspend,
 }
 
-pub fn init(tui: &Tui) -> (Model, Cmd<Message>) {
-    (
-        Model {
-            tui_size: tui.size().unwrap(),
-            ..Default::default()
-        },
-        Cmd::None,
-    )
+pub fn init(tui: &Tui, shutdown: CancellationToken) -> (Model, Cmd<Message>) {
+    let model = Model {
+        tui_size: tui.size().unwrap(),
+        registry: Arc::new(solutions::registry()),
+        shutdown,
+        ..Default::default()
+    };
+
+    match Ledger::load(Ledger::default_path()) {
+        Ok(ledger) => (
+            Model {
+                solved: solved_parts(&ledger),
+                ..model
+            },
+            Cmd::None,
+        ),
+        Err(e) => (
+            model,
+            Cmd::Msg(Message::NotificationMessage(NotificationMessage::Show(
+                Notification::Error(format!("{e:#}")),
+            ))),
+        ),
+    }
+}
+
+/// Every part with a correct answer in the ledger.
+fn solved_parts(ledger: &Ledger) -> BTreeSet<(Day, Part)> {
+    Day::all()
+        .flat_map(|day| Part::ALL.map(|part| (day, part)))
+        .filter(|&(day, part)| ledger.solution(day, part).is_some())
+        .collect()
+}
+
+/// Map a key press to a message, depending on what's currently on screen.
+fn handle_key(model: &Model, key: KeyEvent) -> Option<Message> {
+    if model.submit_dialog.is_open() {
+        return submit_dialog::handle_key(key);
+    }
+    if key.code == KeyCode::Char('q') {
+        return Some(Message::Quit);
+    }
+    router::handle_key(model, key)
 }
 
 pub fn update(mut model: Model, msg: Message) -> (Model, Cmd<Message>) {
     match msg {
-        Message::Increment => {
-            model.counter += 1;
-            if model.counter > 50 {
-                return update(model, Message::Reset);
-            }
-        },
-        Message::Decrement => {
-            model.counter -= 1;
-            if model.counter < -50 {
-                return update(model, Message::Reset);
+        Message::Key(key) => {
+            if let Some(msg) = handle_key(&model, key) {
+                return update(model, msg);
             }
         },
-        Message::Reset => {
-            model.counter = 0;
-        },
         Message::Quit => {
             model.running_state = RunningState::ShouldQuit;
         },
@@ -55,6 +107,10 @@ pub fn update(mut model: Model, msg: Message) -> (Model, Cmd<Message>) {
             );
         },
         Message::Tick => {
+            let (model, _) = update(
+                model,
+                Message::NotificationMessage(NotificationMessage::Tick),
+            );
             return update(
                 model,
                 Message::FpsCounterMessage(fps_counter::FpsCounterMessage::Tick),
@@ -64,9 +120,53 @@ pub fn update(mut model: Model, msg: Message) -> (Model, Cmd<Message>) {
             model.tui_size.width = w;
             model.tui_size.height = h;
         },
+        Message::Navigate(navigation) => {
+            return router::update(model, navigation);
+        },
         Message::FpsCounterMessage(m) => {
             return fps_counter::update(model, m);
         },
+        Message::NotificationMessage(m) => {
+            return notification::update(model, m);
+        },
+        Message::SubmitDialogMessage(m) => {
+            return submit_dialog::update(model, m);
+        },
+        Message::DayPickerMessage(m) => {
+            return day_picker::update(model, m);
+        },
+        Message::DayDetailMessage(m) => {
+            return day_detail::update(model, m);
+        },
+        Message::BenchResultsMessage(m) => {
+            return bench_results::update(model, m);
+        },
+        Message::FetchInput(day) => {
+            let client = model.client.clone();
+            return (
+                model,
+                Cmd::boxed(async move {
+                    let result = client.fetch_input(day).await;
+                    Message::InputFetched(day, result.map_err(|e| format!("{e:#}")))
+                }),
+            );
+        },
+        Message::InputFetched(day, result) => {
+            let notification = match result {
+                Ok(input) => {
+                    model = day_detail::input_fetched(model, day, input.short_hash());
+                    Notification::Info(format!(
+                        "Fetched input for day {day} ({})",
+                        input.short_hash()
+                    ))
+                },
+                Err(e) => Notification::Error(e),
+            };
+            return update(
+                model,
+                Message::NotificationMessage(NotificationMessage::Show(notification)),
+            );
+        },
     }
     (model, Cmd::None)
 }
@@ -74,45 +174,50 @@ pub fn update(mut model: Model, msg: Message) -> (Model, Cmd<Message>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::router::Route;
     use pretty_assertions::assert_eq;
 
     fn model_setup() -> Model {
         Model::default()
     }
 
-    #[test]
-    fn test_increment() {
-        let mut model = model_setup();
-        model.counter = -4;
-        let (model, cmd) = update(model, Message::Increment);
-        assert_eq!(model.counter, -3);
-        assert!(matches!(cmd, Cmd::None));
+    fn key(code: KeyCode) -> Message {
+        Message::Key(KeyEvent::from(code))
     }
 
     #[test]
-    fn test_decrement() {
-        let mut model = model_setup();
-        model.counter = 35;
-        let (model, cmd) = update(model, Message::Decrement);
-        assert_eq!(model.counter, 34);
+    fn test_open_day_and_back() {
+        let model = model_setup();
+        let (model, _) = update(model, key(KeyCode::Right));
+        let (model, _) = update(model, key(KeyCode::Enter));
+        assert_eq!(
+            model.router.current(),
+            Route::DayDetail(Day::new(2).unwrap())
+        );
+        assert_eq!(model.day_detail.day, Day::new(2).unwrap());
+
+        let (model, cmd) = update(model, key(KeyCode::Esc));
+        assert_eq!(model.router.current(), Route::DayPicker);
+        assert_eq!(model.day_picker.selected, Day::new(2).unwrap());
         assert!(matches!(cmd, Cmd::None));
     }
 
     #[test]
-    fn test_increment_saturating() {
-        let mut model = model_setup();
-        model.counter = 50;
-        let (model, cmd) = update(model, Message::Increment);
-        assert_eq!(model.counter, 0);
-        assert!(matches!(cmd, Cmd::None));
+    fn test_quit() {
+        let model = model_setup();
+        let (model, _) = update(model, key(KeyCode::Char('q')));
+        assert_eq!(model.running_state, RunningState::ShouldQuit);
     }
 
     #[test]
-    fn test_decrement_saturating() {
-        let mut model = model_setup();
-        model.counter = -50;
-        let (model, cmd) = update(model, Message::Decrement);
-        assert_eq!(model.counter, 0);
+    fn test_fetch_failure_is_notified() {
+        let model = model_setup();
+        let day = Day::new(2).unwrap();
+        let (model, cmd) = update(
+            model,
+            Message::InputFetched(day, Err("no session token".to_string())),
+        );
+        assert_eq!(notification::height(&model), 3);
         assert!(matches!(cmd, Cmd::None));
     }
 }
@@ -0,0 +1,111 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{command::Cmd, message::Message, model::Model, tui::Frame};
+
+/// How long informational notifications are shown for.
+const INFO_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long error notifications are shown for.
+const ERROR_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Default)]
+pub struct NotificationModel {
+    current: Option<(Notification, Instant)>,
+}
+
+/// A short message shown at the bottom of the screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    Info(String),
+    Error(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NotificationMessage {
+    Show(Notification),
+    Tick,
+}
+
+impl Notification {
+    fn timeout(&self) -> Duration {
+        match self {
+            Notification::Info(_) => INFO_TIMEOUT,
+            Notification::Error(_) => ERROR_TIMEOUT,
+        }
+    }
+}
+
+pub fn update(mut model: Model, msg: NotificationMessage) -> (Model, Cmd<Message>) {
+    let notification_model = &mut model.notification;
+    match msg {
+        NotificationMessage::Show(notification) => {
+            match &notification {
+                Notification::Info(text) => tracing::info!("{text}"),
+                Notification::Error(text) => tracing::error!("{text}"),
+            }
+            notification_model.current = Some((notification, Instant::now()));
+        },
+
+        NotificationMessage::Tick => {
+            if notification_model
+                .current
+                .as_ref()
+                .is_some_and(|(n, shown_at)| shown_at.elapsed() >= n.timeout())
+            {
+                notification_model.current = None;
+            }
+        },
+    }
+    (model, Cmd::None)
+}
+
+/// The number of rows needed to show the current notification, if any.
+pub fn height(model: &Model) -> u16 {
+    if model.notification.current.is_some() {
+        3
+    } else {
+        0
+    }
+}
+
+pub fn view(model: &mut Model, f: &mut Frame, area: Rect) {
+    let Some((notification, _)) = &model.notification.current else {
+        return;
+    };
+
+    let (title, text, color) = match notification {
+        Notification::Info(text) => ("Info", text, Color::Cyan),
+        Notification::Error(text) => ("Error", text, Color::Red),
+    };
+
+    let block = Block::default()
+        .title(block::Title::from(title).alignment(Alignment::Left))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().fg(color));
+
+    f.render_widget(Paragraph::new(text.as_str()).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_notification_expires() {
+        let model = Model::default();
+        let (mut model, _) = update(
+            model,
+            NotificationMessage::Show(Notification::Info("hello".into())),
+        );
+        assert_eq!(height(&model), 3);
+
+        // Pretend the notification was shown long enough ago to expire.
+        model.notification.current.as_mut().unwrap().1 -= INFO_TIMEOUT;
+        let (model, _) = update(model, NotificationMessage::Tick);
+        assert_eq!(height(&model), 0);
+    }
+}
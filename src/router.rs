@@ -0,0 +1,222 @@
+//! Screen routing.
+//!
+//! The app shows one screen at a time, picked from the top of a navigation
+//! stack. Screens are pushed on top of each other as the user navigates deeper,
+//! and popped off again with the back key. Each screen's module provides its own
+//! `handle_key`, `view` and `usage` functions, and the router delegates to
+//! whichever screen is on top.
+
+use aoc2023_common::Day;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+
+use crate::{
+    bench_results, command::Cmd, day_detail, day_picker, message::Message, model::Model, tui::Frame,
+};
+
+/// A screen that can be navigated to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Route {
+    /// The calendar of every day.
+    DayPicker,
+    /// The details for a single day.
+    DayDetail(Day),
+    /// Benchmark results for every day.
+    BenchResults,
+}
+
+/// A stack of screens. The screen on top is the one being shown.
+///
+/// The stack is never empty: the screen at the bottom can't be popped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Router {
+    stack: Vec<Route>,
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        Self::new(Route::DayPicker)
+    }
+}
+
+/// A change to the navigation stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Navigation {
+    /// Show a new screen on top of the current one.
+    Push(Route),
+    /// Go back to the previous screen.
+    Pop,
+    /// Swap the current screen for a new one.
+    Replace(Route),
+}
+
+impl Router {
+    /// Create a router showing `root`, which can never be popped.
+    pub fn new(root: Route) -> Self {
+        Self { stack: vec![root] }
+    }
+
+    /// The screen currently being shown.
+    pub fn current(&self) -> Route {
+        *self.stack.last().unwrap()
+    }
+
+    /// The number of screens on the stack.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Whether there is a screen to go back to.
+    pub fn can_pop(&self) -> bool {
+        self.stack.len() > 1
+    }
+
+    /// Show a new screen on top of the current one.
+    pub fn push(&mut self, route: Route) {
+        self.stack.push(route);
+    }
+
+    /// Go back to the previous screen, returning the screen that was removed.
+    ///
+    /// Returns `None` if there is no previous screen.
+    pub fn pop(&mut self) -> Option<Route> {
+        if self.can_pop() {
+            self.stack.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Swap the current screen for a new one, returning the screen that was replaced.
+    pub fn replace(&mut self, route: Route) -> Route {
+        std::mem::replace(self.stack.last_mut().unwrap(), route)
+    }
+}
+
+pub fn update(mut model: Model, navigation: Navigation) -> (Model, Cmd<Message>) {
+    let router = &mut model.router;
+    match navigation {
+        Navigation::Push(route) => router.push(route),
+        Navigation::Pop => {
+            if router.pop().is_none() {
+                return (model, Cmd::None);
+            }
+        },
+        Navigation::Replace(route) => {
+            router.replace(route);
+        },
+    }
+
+    let route = model.router.current();
+    enter(model, route)
+}
+
+/// Let a screen set itself up when it comes into view.
+fn enter(model: Model, route: Route) -> (Model, Cmd<Message>) {
+    match route {
+        Route::DayPicker => (model, Cmd::None),
+        Route::DayDetail(day) => day_detail::open(model, day),
+        Route::BenchResults => bench_results::open(model),
+    }
+}
+
+/// Map a key press to a message for the current screen.
+pub fn handle_key(model: &Model, key: KeyEvent) -> Option<Message> {
+    if matches!(key.code, KeyCode::Esc | KeyCode::Backspace) && model.router.can_pop() {
+        return Some(Message::Navigate(Navigation::Pop));
+    }
+    match model.router.current() {
+        Route::DayPicker => day_picker::handle_key(model, key),
+        Route::DayDetail(_) => day_detail::handle_key(model, key),
+        Route::BenchResults => bench_results::handle_key(model, key),
+    }
+}
+
+pub fn view(model: &mut Model, f: &mut Frame, area: Rect) {
+    match model.router.current() {
+        Route::DayPicker => day_picker::view(model, f, area),
+        Route::DayDetail(_) => day_detail::view(model, f, area),
+        Route::BenchResults => bench_results::view(model, f, area),
+    }
+}
+
+/// Usage instructions for the current screen.
+pub fn usage(model: &Model) -> Line<'static> {
+    let mut usage = match model.router.current() {
+        Route::DayPicker => day_picker::usage(),
+        Route::DayDetail(_) => day_detail::usage(),
+        Route::BenchResults => bench_results::usage(),
+    };
+    if model.router.can_pop() {
+        usage.spans.extend([
+            "esc".bold().fg(Color::Gray),
+            " to go back, ".fg(Color::DarkGray),
+        ]);
+    }
+    usage
+        .spans
+        .extend(["q".bold().fg(Color::Gray), " to quit.".fg(Color::DarkGray)]);
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn day(day: u8) -> Day {
+        Day::new(day).unwrap()
+    }
+
+    #[test]
+    fn test_push_pop_replace() {
+        let mut router = Router::default();
+        assert_eq!(router.current(), Route::DayPicker);
+
+        router.push(Route::DayDetail(day(1)));
+        assert_eq!(router.depth(), 2);
+        assert_eq!(router.current(), Route::DayDetail(day(1)));
+
+        assert_eq!(
+            router.replace(Route::DayDetail(day(2))),
+            Route::DayDetail(day(1))
+        );
+        assert_eq!(router.depth(), 2);
+        assert_eq!(router.current(), Route::DayDetail(day(2)));
+
+        assert_eq!(router.pop(), Some(Route::DayDetail(day(2))));
+        assert_eq!(router.current(), Route::DayPicker);
+    }
+
+    #[test]
+    fn test_root_cannot_be_popped() {
+        let mut router = Router::default();
+        assert!(!router.can_pop());
+        assert_eq!(router.pop(), None);
+        assert_eq!(router.current(), Route::DayPicker);
+    }
+
+    #[test]
+    fn test_back_key_only_pops_when_possible() {
+        let mut model = Model::default();
+        let back = KeyEvent::from(KeyCode::Esc);
+        assert_eq!(handle_key(&model, back), None);
+
+        model.router.push(Route::DayDetail(day(3)));
+        assert_eq!(
+            handle_key(&model, back),
+            Some(Message::Navigate(Navigation::Pop))
+        );
+    }
+
+    #[test]
+    fn test_push_opens_screen() {
+        let (model, _) = update(
+            Model::default(),
+            Navigation::Push(Route::DayDetail(day(12))),
+        );
+        assert_eq!(model.router.current(), Route::DayDetail(day(12)));
+        assert_eq!(model.day_detail.day, day(12));
+    }
+}
@@ -0,0 +1,392 @@
+//! Runs solutions and other long jobs on the blocking thread pool, so they never
+//! hold up the event loop.
+//!
+//! While a job is running, its progress is polled and streamed back as
+//! [`RunEvent`]s. Cancelling the run's [`CancellationToken`] asks the job to
+//! stop, and reports the run as cancelled straight away rather than waiting for
+//! the job to notice.
+
+use std::{sync::Arc, time::Duration};
+
+use aoc2023_common::{
+    examples::{run_examples, ExampleOutcome},
+    runtime::{self, Cancelled, RunContext, RunNotes},
+    Day, ExampleStore, InputStore, Part, Registry, RunOutput,
+};
+use color_eyre::eyre::{eyre, Result};
+use futures::prelude::*;
+use tokio::{sync::mpsc, task::JoinError};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+
+use crate::diagnostic::Diagnostic;
+
+/// How often to check on a running solution's progress.
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Something that happened while running a solution, or any other blocking job.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunEvent<T = PartRun> {
+    /// The job reported how far along it is, from 0 to 1.
+    Progress(f32),
+    /// The job finished, successfully or not.
+    Finished(Result<T, Diagnostic>),
+    /// The job was cancelled before it finished.
+    Cancelled,
+}
+
+impl<T> RunEvent<T> {
+    /// Whether this is the last event of a run.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Progress(_))
+    }
+}
+
+/// The result of running one part of a day.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartRun {
+    /// Every example passed, and the part was run against the real input.
+    Done {
+        output: RunOutput,
+        examples: usize,
+        /// What the solution noted while running against the real input.
+        notes: RunNotes,
+    },
+    /// An example failed, so the part wasn't run against the real input.
+    ExampleFailed(ExampleOutcome),
+}
+
+/// Run one part of a day against its examples, then against its saved input.
+///
+/// The returned stream ends after the first [final](RunEvent::is_final) event.
+pub fn run_part(
+    registry: Arc<Registry>,
+    inputs: InputStore,
+    examples: ExampleStore,
+    day: Day,
+    part: Part,
+    cancel: CancellationToken,
+) -> impl Stream<Item = RunEvent> + Send + 'static {
+    spawn(cancel, move || {
+        let solution = registry
+            .get(day, part)
+            .ok_or_else(|| eyre!("Day {day} hasn't been solved yet"))?;
+
+        let outcomes = run_examples(solution, &examples.load(day)?);
+        if let Some(failure) = outcomes.iter().find(|outcome| !outcome.passed()) {
+            return Ok(PartRun::ExampleFailed(failure.clone()));
+        }
+
+        // Only show what was noted while running the real input.
+        runtime::take_notes();
+        let input = inputs.load(day)?;
+        Ok(PartRun::Done {
+            output: solution.run(&input.text)?,
+            examples: outcomes.len(),
+            notes: runtime::take_notes(),
+        })
+    })
+}
+
+/// Run `job` on the blocking thread pool, with a [`RunContext`] installed.
+///
+/// Nothing runs until the returned stream is first polled. The stream ends after
+/// the first [final](RunEvent::is_final) event.
+pub fn spawn<T, F>(
+    cancel: CancellationToken,
+    job: F,
+) -> impl Stream<Item = RunEvent<T>> + Send + 'static
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    stream::once(async move { start(cancel, job) }).flatten()
+}
+
+fn start<T, F>(cancel: CancellationToken, job: F) -> ReceiverStream<RunEvent<T>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(16);
+    let context = RunContext::new();
+
+    let mut task = tokio::task::spawn_blocking({
+        let context = context.clone();
+        move || context.enter(job)
+    });
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+        let mut last_progress = None;
+        let event = loop {
+            tokio::select! {
+                result = &mut task => break match result {
+                    Ok(Ok(output)) => RunEvent::Finished(Ok(output)),
+                    Ok(Err(e)) if e.chain().any(|e| e.is::<Cancelled>()) => RunEvent::Cancelled,
+                    Ok(Err(e)) => RunEvent::Finished(Err(Diagnostic::from_report(&e))),
+                    Err(e) => RunEvent::Finished(Err(join_error(e).into())),
+                },
+                _ = cancel.cancelled() => {
+                    // The job may keep running in the background until it next
+                    // checks for cancellation, but nobody is waiting for it anymore.
+                    context.cancel();
+                    break RunEvent::Cancelled;
+                },
+                _ = interval.tick() => {
+                    let progress = context.progress();
+                    if let Some(p) = progress.filter(|_| progress != last_progress) {
+                        last_progress = progress;
+                        let _ = tx.send(RunEvent::Progress(p)).await;
+                    }
+                },
+            }
+        };
+        let _ = tx.send(event).await;
+    });
+
+    ReceiverStream::new(rx)
+}
+
+/// Describe why a job's task didn't finish, including what it panicked with.
+fn join_error(e: JoinError) -> String {
+    if !e.is_panic() {
+        return format!("Error joining solution task: {e}");
+    }
+    let payload = e.into_panic();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str));
+    match message {
+        Some(message) => format!("Solution panicked: {message}"),
+        None => "Solution panicked".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2023_common::{cycle, Answer, Cycle, Parser, Solution};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Part 1 spins until it's cancelled, part 2 finds a cycle as long as the
+    /// input, which must be a single word.
+    struct Spin;
+
+    impl Solution for Spin {
+        type Answer1 = u64;
+        type Answer2 = usize;
+        type Input = usize;
+
+        const TITLE: &'static str = "Spinning";
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            Ok(Parser::parse_all(input, Parser::word)?.len())
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Self::Answer1> {
+            for i in 0.. {
+                runtime::check_cancelled()?;
+                runtime::progress(i.min(*input), *input);
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            unreachable!()
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Self::Answer2> {
+            Ok(cycle::hashed(0, |n| (n + 1) % input)?.period)
+        }
+    }
+
+    fn setup() -> (Arc<Registry>, InputStore, ExampleStore, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Registry::new().register(Day::FIRST, Spin);
+        let inputs = InputStore::new(dir.path());
+        inputs.save(Day::FIRST, "spin").unwrap();
+        let examples = ExampleStore::new(dir.path());
+        (Arc::new(registry), inputs, examples, dir)
+    }
+
+    #[tokio::test]
+    async fn test_run_finishes() {
+        let (registry, inputs, examples, dir) = setup();
+        std::fs::create_dir_all(dir.path().join("examples")).unwrap();
+        std::fs::write(
+            examples.path(Day::FIRST),
+            "[[example]]\ninput = \"ab\"\npart2 = \"2\"\n",
+        )
+        .unwrap();
+
+        let events: Vec<_> = run_part(
+            registry,
+            inputs,
+            examples,
+            Day::FIRST,
+            Part::Two,
+            CancellationToken::new(),
+        )
+        .collect()
+        .await;
+
+        let Some(RunEvent::Finished(Ok(PartRun::Done {
+            output,
+            examples,
+            notes,
+        }))) = events.last()
+        else {
+            panic!("run should finish: {events:?}");
+        };
+        assert_eq!(output.answer, "4");
+        assert_eq!(*examples, 1);
+        // The example's cycle isn't included.
+        assert_eq!(
+            notes.cycles,
+            vec![Cycle {
+                start: 0,
+                period: 4
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_example_skips_input() {
+        let (registry, inputs, examples, dir) = setup();
+        std::fs::create_dir_all(dir.path().join("examples")).unwrap();
+        std::fs::write(
+            examples.path(Day::FIRST),
+            "[[example]]\ninput = \"ab\"\npart2 = \"2\"\n\n[[example]]\ninput = \"abc\"\npart2 = \"4\"\n",
+        )
+        .unwrap();
+
+        let events: Vec<_> = run_part(
+            registry,
+            inputs,
+            examples,
+            Day::FIRST,
+            Part::Two,
+            CancellationToken::new(),
+        )
+        .collect()
+        .await;
+
+        let [RunEvent::Finished(Ok(PartRun::ExampleFailed(failure)))] = &events[..] else {
+            panic!("example should fail: {events:?}");
+        };
+        assert_eq!(failure.index, 2);
+        assert_eq!(failure.actual, Ok(Answer::from(3usize)));
+    }
+
+    #[tokio::test]
+    async fn test_run_cancelled() {
+        let (registry, inputs, examples, _dir) = setup();
+        let cancel = CancellationToken::new();
+        let mut events = Box::pin(run_part(
+            registry,
+            inputs,
+            examples,
+            Day::FIRST,
+            Part::One,
+            cancel.clone(),
+        ));
+
+        assert!(matches!(events.next().await, Some(RunEvent::Progress(_))));
+        cancel.cancel();
+        let rest: Vec<_> = events.collect().await;
+        assert_eq!(rest.last(), Some(&RunEvent::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_missing_input_fails() {
+        let (registry, _, examples, _dir) = setup();
+        let empty = tempfile::tempdir().unwrap();
+        let events: Vec<_> = run_part(
+            registry,
+            InputStore::new(empty.path()),
+            examples,
+            Day::FIRST,
+            Part::One,
+            CancellationToken::new(),
+        )
+        .collect()
+        .await;
+        assert!(matches!(events[..], [RunEvent::Finished(Err(_))]));
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_diagnostic() {
+        let (registry, inputs, examples, _dir) = setup();
+        inputs.save(Day::FIRST, "sp!n").unwrap();
+        let events: Vec<_> = run_part(
+            registry,
+            inputs,
+            examples,
+            Day::FIRST,
+            Part::Two,
+            CancellationToken::new(),
+        )
+        .collect()
+        .await;
+
+        let [RunEvent::Finished(Err(diagnostic))] = &events[..] else {
+            panic!("parsing should fail: {events:?}");
+        };
+        assert_eq!(
+            diagnostic.context,
+            vec!["Error running day 1 part 2", "Error parsing puzzle input"]
+        );
+        let parse_error = diagnostic.parse_error.as_ref().unwrap();
+        assert_eq!((parse_error.line, parse_error.column), (1, 3));
+    }
+
+    /// Panics when solving either part.
+    struct Panics;
+
+    impl Solution for Panics {
+        type Answer1 = u64;
+        type Answer2 = u64;
+        type Input = ();
+
+        const TITLE: &'static str = "Panicking";
+
+        fn parse(&self, _input: &str) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(&self, _input: &Self::Input) -> Result<Self::Answer1> {
+            panic!("oh no")
+        }
+
+        fn part2(&self, _input: &Self::Input) -> Result<Self::Answer2> {
+            panic!("oh no {}", 2)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panic_fails_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Arc::new(Registry::new().register(Day::FIRST, Panics));
+        let inputs = InputStore::new(dir.path());
+        inputs.save(Day::FIRST, "").unwrap();
+
+        for (part, message) in [(Part::One, "oh no"), (Part::Two, "oh no 2")] {
+            let events: Vec<_> = run_part(
+                registry.clone(),
+                inputs.clone(),
+                ExampleStore::new(dir.path()),
+                Day::FIRST,
+                part,
+                CancellationToken::new(),
+            )
+            .collect()
+            .await;
+            assert_eq!(
+                events,
+                vec![RunEvent::Finished(Err(Diagnostic::from(format!(
+                    "Solution panicked: {message}"
+                ))))]
+            );
+        }
+    }
+}
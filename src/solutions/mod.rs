@@ -0,0 +1,45 @@
+//! Solutions to each day's puzzle.
+//!
+//! Each day lives in its own `dayNN` module, and is registered in [`registry()`].
+
+use aoc2023_common::Registry;
+
+/// Build the registry of every solved day.
+pub fn registry() -> Registry {
+    Registry::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc2023_common::{examples::assert_examples, ExampleStore};
+
+    use super::*;
+    use crate::utils::get_puzzles_dir;
+
+    /// The examples checked into the repository, which the TUI also runs.
+    fn examples() -> ExampleStore {
+        ExampleStore::new(get_puzzles_dir())
+    }
+
+    /// Check every solution against the checked-in examples.
+    #[test]
+    fn test_examples() {
+        assert_examples(&registry(), &examples());
+    }
+
+    /// Every solved day needs examples, or [`test_examples`] can't check it.
+    #[test]
+    fn test_every_day_has_examples() {
+        let store = examples();
+        let missing: Vec<String> = registry()
+            .days()
+            .filter(|&day| !store.path(day).exists())
+            .map(|day| day.to_string())
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "Days without examples: {}",
+            missing.join(", ")
+        );
+    }
+}
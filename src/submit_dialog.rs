@@ -0,0 +1,217 @@
+use aoc2023_common::{Day, Part};
+use color_eyre::eyre::Report;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    answers::KnownAnswers,
+    command::Cmd,
+    ledger::{Ledger, SubmitOutcome},
+    message::Message,
+    model::Model,
+    notification::{Notification, NotificationMessage},
+    tui::Frame,
+};
+
+/// Asks for confirmation before submitting an answer to the website.
+#[derive(Debug, Default)]
+pub struct SubmitDialogModel {
+    pending: Option<PendingSubmission>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingSubmission {
+    day: Day,
+    part: Part,
+    answer: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SubmitDialogMessage {
+    /// Ask the user whether `answer` should be submitted.
+    Open {
+        day: Day,
+        part: Part,
+        answer: String,
+    },
+    /// Submit the pending answer.
+    Confirm,
+    /// Close the dialog without submitting anything.
+    Cancel,
+    /// The website responded to a submitted answer.
+    Submitted {
+        day: Day,
+        part: Part,
+        result: Result<SubmitOutcome, String>,
+    },
+}
+
+impl SubmitDialogModel {
+    /// Whether the dialog is currently open.
+    pub fn is_open(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+pub fn handle_key(key: KeyEvent) -> Option<Message> {
+    let msg = match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => SubmitDialogMessage::Confirm,
+        KeyCode::Char('n') | KeyCode::Esc => SubmitDialogMessage::Cancel,
+        _ => return None,
+    };
+    Some(Message::SubmitDialogMessage(msg))
+}
+
+pub fn update(mut model: Model, msg: SubmitDialogMessage) -> (Model, Cmd<Message>) {
+    let dialog_model = &mut model.submit_dialog;
+    match msg {
+        SubmitDialogMessage::Open { day, part, answer } => {
+            dialog_model.pending = Some(PendingSubmission { day, part, answer });
+        },
+
+        SubmitDialogMessage::Confirm => {
+            let Some(PendingSubmission { day, part, answer }) = dialog_model.pending.take() else {
+                return (model, Cmd::None);
+            };
+            let client = model.client.clone();
+            return (
+                model,
+                Cmd::boxed(async move {
+                    let result = async {
+                        let mut ledger = Ledger::load(Ledger::default_path())?;
+                        let outcome = client
+                            .submit_answer(&mut ledger, day, part, &answer)
+                            .await?;
+                        if outcome.is_solved() {
+                            KnownAnswers::load(KnownAnswers::default_path())?
+                                .record(day, part, answer)?;
+                        }
+                        Ok::<_, Report>(outcome)
+                    }
+                    .await;
+                    Message::SubmitDialogMessage(SubmitDialogMessage::Submitted {
+                        day,
+                        part,
+                        result: result.map_err(|e| format!("{e:#}")),
+                    })
+                }),
+            );
+        },
+
+        SubmitDialogMessage::Cancel => {
+            dialog_model.pending = None;
+        },
+
+        SubmitDialogMessage::Submitted { day, part, result } => {
+            let notification = match result {
+                Ok(outcome) if outcome.is_solved() => {
+                    model.solved.insert((day, part));
+                    Notification::Info(format!("Day {day} part {part}: {outcome}"))
+                },
+                Ok(outcome) => Notification::Error(format!("Day {day} part {part}: {outcome}")),
+                Err(e) => Notification::Error(e),
+            };
+            return (
+                model,
+                Cmd::Msg(Message::NotificationMessage(NotificationMessage::Show(
+                    notification,
+                ))),
+            );
+        },
+    }
+    (model, Cmd::None)
+}
+
+pub fn view(model: &mut Model, f: &mut Frame, area: Rect) {
+    let Some(PendingSubmission { day, part, answer }) = &model.submit_dialog.pending else {
+        return;
+    };
+
+    let area = centered_rect(area, 50, 7);
+    let block = Block::default()
+        .title(block::Title::from("Submit answer?").alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::new().fg(Color::Yellow));
+
+    let text = vec![
+        Line::from(vec![
+            "Day ".into(),
+            day.to_string().bold(),
+            ", part ".into(),
+            part.to_string().bold(),
+        ]),
+        Line::from(answer.as_str().bold().fg(Color::Yellow)),
+        Line::default(),
+        Line::from(vec![
+            "y".bold().fg(Color::Gray),
+            " to submit, ".fg(Color::DarkGray),
+            "n".bold().fg(Color::Gray),
+            " to cancel.".fg(Color::DarkGray),
+        ]),
+    ];
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .block(block),
+        area,
+    );
+}
+
+/// A rectangle of at most `width` by `height` cells, centered inside `area`.
+fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn open(model: Model) -> Model {
+        let (model, cmd) = update(
+            model,
+            SubmitDialogMessage::Open {
+                day: Day::new(5).unwrap(),
+                part: Part::One,
+                answer: "35".into(),
+            },
+        );
+        assert!(matches!(cmd, Cmd::None));
+        model
+    }
+
+    #[test]
+    fn test_cancel_closes_dialog() {
+        let model = open(Model::default());
+        assert!(model.submit_dialog.is_open());
+        let (model, cmd) = update(model, SubmitDialogMessage::Cancel);
+        assert!(!model.submit_dialog.is_open());
+        assert!(matches!(cmd, Cmd::None));
+    }
+
+    #[test]
+    fn test_confirm_submits() {
+        let model = open(Model::default());
+        let (model, cmd) = update(model, SubmitDialogMessage::Confirm);
+        assert!(!model.submit_dialog.is_open());
+        assert!(matches!(cmd, Cmd::Future(_)));
+    }
+
+    #[test]
+    fn test_confirm_without_dialog_does_nothing() {
+        let (model, cmd) = update(Model::default(), SubmitDialogMessage::Confirm);
+        assert_eq!(model.submit_dialog.pending, None);
+        assert!(matches!(cmd, Cmd::None));
+    }
+}
@@ -1,4 +1,4 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::KeyEventKind;
 use futures::prelude::*;
 use tokio::sync::mpsc::UnboundedReceiver;
 
@@ -39,7 +39,9 @@ pub fn tui_event_subscription(
                     Some(Message::Resize(w, h))
                 },
 
-                TuiEvent::Key(key) => handle_key_event(key),
+                // Key presses are mapped to messages by the model, since what
+                // they do depends on what's on screen.
+                TuiEvent::Key(key) if key.kind == KeyEventKind::Press => Some(Message::Key(key)),
 
                 _ => None,
             }
@@ -53,16 +55,3 @@ pub fn tui_event_subscription(
 pub fn subscriptions(model: Model) -> (Model, Subscription<'static, Message>) {
     (model, Box::pin(tokio_stream::empty()))
 }
-
-fn handle_key_event(key: KeyEvent) -> Option<Message> {
-    if key.kind == KeyEventKind::Press {
-        return match key.code {
-            KeyCode::Char('j') => Some(Message::Increment),
-            KeyCode::Char('k') => Some(Message::Decrement),
-            KeyCode::Char('q') => Some(Message::Quit),
-            _ => None,
-        };
-    }
-
-    None
-}
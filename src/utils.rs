@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use aoc2023_common::runtime;
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
@@ -17,6 +18,8 @@ lazy_static! {
     pub static ref DATA_FOLDER: Option<PathBuf> = std::env::var(format!("{}_DATA", PROJECT_NAME.clone())).ok().map(PathBuf::from);
     /// The name of the app's config folder.
     pub static ref CONFIG_FOLDER: Option<PathBuf> = std::env::var(format!("{}_CONFIG", PROJECT_NAME.clone())).ok().map(PathBuf::from);
+    /// Overrides the base URL of the Advent of Code website, e.g. for testing.
+    pub static ref BASE_URL: Option<String> = std::env::var(format!("{}_BASE_URL", PROJECT_NAME.clone())).ok();
     /// The name of the environment variable to read for log levels.
     pub static ref LOG_ENV: String = format!("{}_LOG_LEVEL", PROJECT_NAME.clone());
     /// The name of the file to store logs in.
@@ -48,6 +51,13 @@ pub fn initialize_panic_handler() -> Result<()> {
 
     // Wrap the actual eyre hook in our own hook so we can customize it a lot.
     std::panic::set_hook(Box::new(move |panic_info| {
+        // A panicking solution is reported as a failed run, so leave the terminal
+        // UI alone and just log it.
+        if runtime::is_running() {
+            tracing::error!("Solution panicked: {panic_info}");
+            return;
+        }
+
         // Try to exit the terminal UI properly on panic.
         if let Ok(mut t) = crate::tui::Tui::new() {
             if let Err(r) = t.exit() {
@@ -103,6 +113,12 @@ pub fn get_data_dir() -> PathBuf {
     directory
 }
 
+/// Resolve the location of the `puzzles/` directory, which is checked into the
+/// repository and holds each day's examples.
+pub fn get_puzzles_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("puzzles")
+}
+
 /// Resolve the location of the `.config/` directory.
 pub fn get_config_dir() -> PathBuf {
     let directory = if let Some(s) = CONFIG_FOLDER.clone() {
@@ -1,25 +1,21 @@
 use ratatui::{prelude::*, widgets::*};
 
-use crate::{fps_counter, model::Model, tui::Frame};
+use crate::{fps_counter, model::Model, notification, router, submit_dialog, tui::Frame};
 
 pub fn view(model: &mut Model, f: &mut Frame) {
     let rects = Layout::new(
         Direction::Vertical,
-        [Constraint::Percentage(100), Constraint::Min(3)],
+        [
+            Constraint::Percentage(100),
+            Constraint::Length(notification::height(model)),
+            Constraint::Min(3),
+        ],
     )
     .split(f.size());
 
-    let counter_block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(Style::new().dim());
+    router::view(model, f, rects[0]);
 
-    f.render_widget(
-        Paragraph::new(format!("Counter: {}", model.counter)),
-        counter_block.inner(rects[0]),
-    );
-
-    f.render_widget(counter_block, rects[0]);
+    notification::view(model, f, rects[1]);
 
     let rects = Layout::new(
         Direction::Horizontal,
@@ -28,7 +24,7 @@ pub fn view(model: &mut Model, f: &mut Frame) {
             Constraint::Min(20),         // "30.00fps, 30.00tps" = 18 characters + 2 for border
         ],
     )
-    .split(rects[1]);
+    .split(rects[2]);
 
     // Render usage
     let usage_block = Block::default()
@@ -38,14 +34,7 @@ pub fn view(model: &mut Model, f: &mut Frame) {
         .border_style(Style::new().dim());
 
     f.render_widget(
-        Paragraph::new(Line::from(vec![
-            "j".bold().fg(Color::Gray),
-            " to increment, ".fg(Color::DarkGray),
-            "k".bold().fg(Color::Gray),
-            " to decrement, ".fg(Color::DarkGray),
-            "q".bold().fg(Color::Gray),
-            " to quit.".fg(Color::DarkGray),
-        ])),
+        Paragraph::new(router::usage(model)),
         usage_block.inner(rects[0]),
     );
 
@@ -59,4 +48,7 @@ pub fn view(model: &mut Model, f: &mut Frame) {
 
     fps_counter::view(model, f, fps_block.inner(rects[1]));
     f.render_widget(fps_block, rects[1]);
+
+    // Render dialogs on top of everything else
+    submit_dialog::view(model, f, f.size());
 }